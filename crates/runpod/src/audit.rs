@@ -0,0 +1,220 @@
+//! Audit log entries.
+//!
+//! The API has no account-wide audit log. Entries are only exposed per support
+//! impersonation session (`User.impersonations` and `User.activeImpersonation`), and the
+//! `auditLogs` field takes no arguments, so filtering happens client-side.
+//!
+//! The connection does report `pageInfo`, but with no `after` argument there is no way to
+//! ask for the next page. A session whose first page says `hasNextPage` is reported as a
+//! [`RunpodError::IncompleteResponse`] after its entries, rather than dropped silently.
+
+use crate::error::Result;
+use crate::gql::{audit_logs, DateTime};
+use crate::RunpodError;
+use serde::Serialize;
+use typed_builder::TypedBuilder;
+
+/// One audit log entry, recorded during a support impersonation session.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditLog {
+    pub impersonation_id: String,
+    pub timestamp: Option<DateTime>,
+    pub action: Option<String>,
+    pub resource_type: Option<String>,
+    pub resource_id: Option<String>,
+    pub actor_id: Option<String>,
+    pub email: Option<String>,
+    pub owner_id: Option<String>,
+    /// Details of the action as reported by the API, usually a JSON document.
+    pub value: Option<String>,
+}
+
+impl AuditLog {
+    fn new(impersonation_id: &str, log: audit_logs::ImpersonationAuditLogsAuditLogsEdges) -> Self {
+        Self {
+            impersonation_id: impersonation_id.to_string(),
+            timestamp: log.timestamp,
+            action: log.action,
+            resource_type: log.resource_type,
+            resource_id: log.resource_id,
+            actor_id: log.actor_id,
+            email: log.email,
+            owner_id: log.owner_id,
+            value: log.value,
+        }
+    }
+}
+
+/// Entries of every impersonation session, oldest first, followed by an error for each
+/// session with more entries than its first page. The active session is usually also
+/// listed in `impersonations`, so sessions are taken once each.
+pub(crate) fn collect(
+    impersonations: Option<Vec<Option<audit_logs::ImpersonationAuditLogs>>>,
+    active: Option<audit_logs::ImpersonationAuditLogs>,
+) -> Vec<Result<AuditLog>> {
+    let mut seen = Vec::new();
+    let mut logs = Vec::new();
+    let mut truncated = Vec::new();
+    for impersonation in impersonations.into_iter().flatten().flatten().chain(active) {
+        if seen.contains(&impersonation.id) {
+            continue;
+        }
+        let connection = impersonation.audit_logs;
+        if connection
+            .as_ref()
+            .and_then(|c| c.page_info.as_ref())
+            .and_then(|p| p.has_next_page)
+            .unwrap_or(false)
+        {
+            truncated.push(Err(RunpodError::IncompleteResponse(format!(
+                "impersonation {} has more audit log entries than the API returns in one \
+                 page, and auditLogs takes no cursor",
+                impersonation.id
+            ))));
+        }
+        let edges = connection.and_then(|c| c.edges).unwrap_or_default();
+        logs.extend(
+            edges
+                .into_iter()
+                .flatten()
+                .map(|log| AuditLog::new(&impersonation.id, log)),
+        );
+        seen.push(impersonation.id);
+    }
+    logs.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    logs.into_iter().map(Ok).chain(truncated).collect()
+}
+
+/// Which audit log entries [`RunpodClient::audit_logs`](crate::RunpodClient::audit_logs)
+/// returns.
+///
+/// Without the `chrono` feature timestamps are compared as text, which only orders them
+/// correctly when the API and the filter use the same format.
+#[derive(Debug, Clone, Default, TypedBuilder)]
+pub struct AuditLogFilter {
+    /// Only entries at or after this time.
+    #[builder(default, setter(strip_option))]
+    pub since: Option<DateTime>,
+    /// Only entries before this time.
+    #[builder(default, setter(strip_option))]
+    pub until: Option<DateTime>,
+    /// Only entries about this kind of resource, e.g. `pod`.
+    #[builder(default, setter(strip_option, into))]
+    pub resource_type: Option<String>,
+    #[builder(default, setter(strip_option, into))]
+    pub resource_id: Option<String>,
+}
+
+impl AuditLogFilter {
+    /// Entries without a timestamp only pass when no time range is set.
+    pub fn matches(&self, log: &AuditLog) -> bool {
+        let in_range = match &log.timestamp {
+            Some(at) => {
+                self.since.as_ref().is_none_or(|since| at >= since)
+                    && self.until.as_ref().is_none_or(|until| at < until)
+            }
+            None => self.since.is_none() && self.until.is_none(),
+        };
+        let is =
+            |wanted: &Option<String>, actual: &Option<String>| wanted.is_none() || wanted == actual;
+        in_range
+            && is(&self.resource_type, &log.resource_type)
+            && is(&self.resource_id, &log.resource_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datetime::parse_datetime;
+
+    fn impersonation(id: &str, times: &[&str]) -> audit_logs::ImpersonationAuditLogs {
+        paged_impersonation(id, times, false)
+    }
+
+    fn paged_impersonation(
+        id: &str,
+        times: &[&str],
+        has_next_page: bool,
+    ) -> audit_logs::ImpersonationAuditLogs {
+        let edges = times
+            .iter()
+            .map(|time| {
+                Some(audit_logs::ImpersonationAuditLogsAuditLogsEdges {
+                    actor_id: Some("support".into()),
+                    email: None,
+                    owner_id: None,
+                    resource_type: Some("pod".into()),
+                    resource_id: Some("p1".into()),
+                    action: Some("stop".into()),
+                    value: None,
+                    timestamp: Some(parse_datetime(time).unwrap()),
+                })
+            })
+            .collect();
+        audit_logs::ImpersonationAuditLogs {
+            id: id.into(),
+            audit_logs: Some(audit_logs::ImpersonationAuditLogsAuditLogs {
+                edges: Some(edges),
+                page_info: Some(audit_logs::ImpersonationAuditLogsAuditLogsPageInfo {
+                    has_next_page: Some(has_next_page),
+                    end_cursor: None,
+                }),
+            }),
+        }
+    }
+
+    #[test]
+    fn collects_each_session_once_in_time_order() {
+        let logs = collect(
+            Some(vec![
+                Some(impersonation("a", &["2026-01-03T00:00:00Z"])),
+                Some(impersonation("b", &["2026-01-01T00:00:00Z"])),
+            ]),
+            Some(impersonation("b", &["2026-01-01T00:00:00Z"])),
+        );
+        let sessions: Vec<_> = logs
+            .iter()
+            .map(|l| l.as_ref().unwrap().impersonation_id.as_str())
+            .collect();
+        assert_eq!(sessions, vec!["b", "a"]);
+    }
+
+    #[test]
+    fn reports_sessions_with_more_pages() {
+        let logs = collect(
+            Some(vec![
+                Some(paged_impersonation("a", &["2026-01-03T00:00:00Z"], true)),
+                Some(impersonation("b", &["2026-01-01T00:00:00Z"])),
+            ]),
+            None,
+        );
+        assert_eq!(logs.len(), 3);
+        assert!(logs[..2].iter().all(Result::is_ok));
+        assert!(matches!(
+            &logs[2],
+            Err(RunpodError::IncompleteResponse(message)) if message.contains("impersonation a")
+        ));
+    }
+
+    #[test]
+    fn filters_by_time_and_resource() {
+        let logs: Vec<_> = collect(
+            Some(vec![Some(impersonation(
+                "a",
+                &["2026-01-01T00:00:00Z", "2026-02-01T00:00:00Z"],
+            ))]),
+            None,
+        )
+        .into_iter()
+        .map(|log| log.unwrap())
+        .collect();
+        let filter = AuditLogFilter::builder()
+            .since(parse_datetime("2026-01-15").unwrap())
+            .resource_type("pod")
+            .build();
+        assert_eq!(logs.iter().filter(|l| filter.matches(l)).count(), 1);
+        let other = AuditLogFilter::builder().resource_type("template").build();
+        assert!(!logs.iter().any(|l| other.matches(l)));
+    }
+}
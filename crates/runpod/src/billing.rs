@@ -0,0 +1,389 @@
+use crate::gql::billing_query;
+use crate::gql::DateTime;
+use crate::RunpodError;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use tracing::error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BillingGranularity {
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl FromStr for BillingGranularity {
+    type Err = RunpodError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "hourly" => Ok(BillingGranularity::Hourly),
+            "daily" => Ok(BillingGranularity::Daily),
+            "weekly" => Ok(BillingGranularity::Weekly),
+            "monthly" => Ok(BillingGranularity::Monthly),
+            other => Err(RunpodError::InvalidInput(format!(
+                "unknown billing granularity: {other}"
+            ))),
+        }
+    }
+}
+
+impl From<BillingGranularity> for billing_query::BillingGranularity {
+    fn from(granularity: BillingGranularity) -> Self {
+        match granularity {
+            BillingGranularity::Hourly => billing_query::BillingGranularity::HOURLY,
+            BillingGranularity::Daily => billing_query::BillingGranularity::DAILY,
+            BillingGranularity::Weekly => billing_query::BillingGranularity::WEEKLY,
+            BillingGranularity::Monthly => billing_query::BillingGranularity::MONTHLY,
+        }
+    }
+}
+
+/// How [`BillingReport::rows`] groups costs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BillingGroupBy {
+    /// GPU cloud spend per GPU type.
+    Gpu,
+    /// Summary spend per product: GPU cloud, CPU cloud, serverless, storage and endpoints.
+    Category,
+    /// Daily charges split into pod, disk, API and serverless charges.
+    Charge,
+}
+
+impl FromStr for BillingGroupBy {
+    type Err = RunpodError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "gpu" => Ok(BillingGroupBy::Gpu),
+            "category" => Ok(BillingGroupBy::Category),
+            "charge" => Ok(BillingGroupBy::Charge),
+            other => Err(RunpodError::InvalidInput(format!(
+                "unknown billing group: {other}"
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GpuCloudBilling {
+    pub gpu_type_id: Option<String>,
+    pub time: Option<DateTime>,
+    pub time_billed_seconds: Option<i64>,
+    pub amount: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CpuCloudBilling {
+    pub cpu_flavor_id: Option<String>,
+    pub time: Option<DateTime>,
+    pub time_billed_seconds: Option<i64>,
+    pub amount: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ServerlessBilling {
+    pub time: Option<DateTime>,
+    pub amount: Option<f64>,
+    pub time_billed_seconds: Option<i64>,
+    pub endpoint_id: Option<String>,
+    pub gpu_type_id: Option<String>,
+    pub instance_id: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StorageBilling {
+    pub time: Option<DateTime>,
+    pub amount: Option<f64>,
+    pub disk_space_billed_gb: Option<i64>,
+    pub network_storage_disk_space_billed_gb: Option<i64>,
+    pub network_storage_amount: Option<f64>,
+    pub gpu_disk_space_billed_gb: Option<i64>,
+    pub gpu_storage_amount: Option<f64>,
+    pub cpu_disk_space_billed_gb: Option<i64>,
+    pub cpu_storage_amount: Option<f64>,
+    pub sls_disk_space_billed_gb: Option<i64>,
+    pub sls_storage_amount: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SummaryBilling {
+    pub time: Option<DateTime>,
+    pub gpu_cloud_amount: Option<f64>,
+    pub cpu_cloud_amount: Option<f64>,
+    pub serverless_amount: Option<f64>,
+    pub storage_amount: Option<f64>,
+    pub runpod_endpoint_amount: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub enum ChargeType {
+    Serverless,
+    Pod,
+    Api,
+    Storage,
+    SavingsPlan,
+}
+
+#[derive(Debug, Clone)]
+pub struct DailyCharge {
+    pub amount: Option<f64>,
+    pub updated_at: Option<DateTime>,
+    pub disk_charges: Option<f64>,
+    pub pod_charges: Option<f64>,
+    pub api_charges: Option<f64>,
+    pub serverless_charges: Option<f64>,
+    pub charge_type: Option<ChargeType>,
+}
+
+/// Billing series for the account at a single granularity, plus the daily charge breakdown.
+#[derive(Debug, Clone, Default)]
+pub struct BillingReport {
+    pub gpu_cloud: Vec<GpuCloudBilling>,
+    pub cpu_cloud: Vec<CpuCloudBilling>,
+    pub serverless: Vec<ServerlessBilling>,
+    pub storage: Vec<StorageBilling>,
+    pub summary: Vec<SummaryBilling>,
+    pub daily_charges: Vec<DailyCharge>,
+}
+
+/// One line of a cost report.
+#[cfg_attr(feature = "tabled", derive(tabled::Tabled))]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CostRow {
+    pub period: DateTime,
+    pub group: String,
+    pub amount: f64,
+}
+
+impl BillingReport {
+    /// Flatten the report into rows grouped by `group_by`, summing amounts that share a
+    /// period and group. Periods before `since` are dropped.
+    ///
+    /// Without the `chrono` feature periods are compared as strings, so `since` should use
+    /// the same ISO 8601 format as the API (a date such as `2026-09-01` works as a prefix).
+    pub fn rows(&self, group_by: BillingGroupBy, since: Option<&DateTime>) -> Vec<CostRow> {
+        let mut totals: BTreeMap<(DateTime, String), f64> = BTreeMap::new();
+        let mut add = |time: &Option<DateTime>, group: &str, amount: Option<f64>| {
+            let (Some(time), Some(amount)) = (time, amount) else {
+                return;
+            };
+            if since.is_some_and(|since| time < since) {
+                return;
+            }
+            *totals
+                .entry((time.to_owned(), group.to_string()))
+                .or_default() += amount;
+        };
+
+        match group_by {
+            BillingGroupBy::Gpu => {
+                for row in &self.gpu_cloud {
+                    let gpu = row.gpu_type_id.as_deref().unwrap_or("unknown");
+                    add(&row.time, gpu, row.amount);
+                }
+            }
+            BillingGroupBy::Category => {
+                for row in &self.summary {
+                    add(&row.time, "gpu_cloud", row.gpu_cloud_amount);
+                    add(&row.time, "cpu_cloud", row.cpu_cloud_amount);
+                    add(&row.time, "serverless", row.serverless_amount);
+                    add(&row.time, "storage", row.storage_amount);
+                    add(&row.time, "runpod_endpoint", row.runpod_endpoint_amount);
+                }
+            }
+            BillingGroupBy::Charge => {
+                for row in &self.daily_charges {
+                    add(&row.updated_at, "pod", row.pod_charges);
+                    add(&row.updated_at, "disk", row.disk_charges);
+                    add(&row.updated_at, "api", row.api_charges);
+                    add(&row.updated_at, "serverless", row.serverless_charges);
+                }
+            }
+        }
+
+        totals
+            .into_iter()
+            .map(|((period, group), amount)| CostRow {
+                period,
+                group,
+                amount,
+            })
+            .collect()
+    }
+}
+
+impl From<billing_query::BillingQueryMyself> for BillingReport {
+    fn from(myself: billing_query::BillingQueryMyself) -> Self {
+        let daily_charges = myself
+            .daily_charges
+            .into_iter()
+            .flatten()
+            .map(Into::into)
+            .collect();
+        let Some(billing) = myself.billing else {
+            return Self {
+                daily_charges,
+                ..Default::default()
+            };
+        };
+        fn collect<T, U: From<T>>(v: Option<Vec<Option<T>>>) -> Vec<U> {
+            v.unwrap_or_default()
+                .into_iter()
+                .flatten()
+                .map(Into::into)
+                .collect()
+        }
+        Self {
+            gpu_cloud: collect(billing.gpu_cloud),
+            cpu_cloud: collect(billing.cpu_cloud),
+            serverless: collect(billing.serverless),
+            storage: collect(billing.storage),
+            summary: collect(billing.summary),
+            daily_charges,
+        }
+    }
+}
+
+impl From<billing_query::BillingQueryMyselfBillingGpuCloud> for GpuCloudBilling {
+    fn from(row: billing_query::BillingQueryMyselfBillingGpuCloud) -> Self {
+        Self {
+            gpu_type_id: row.gpu_type_id,
+            time: row.time,
+            time_billed_seconds: row.time_billed_seconds,
+            amount: row.amount,
+        }
+    }
+}
+
+impl From<billing_query::BillingQueryMyselfBillingCpuCloud> for CpuCloudBilling {
+    fn from(row: billing_query::BillingQueryMyselfBillingCpuCloud) -> Self {
+        Self {
+            cpu_flavor_id: row.cpu_flavor_id,
+            time: row.time,
+            time_billed_seconds: row.time_billed_seconds,
+            amount: row.amount,
+        }
+    }
+}
+
+impl From<billing_query::BillingQueryMyselfBillingServerless> for ServerlessBilling {
+    fn from(row: billing_query::BillingQueryMyselfBillingServerless) -> Self {
+        Self {
+            time: row.time,
+            amount: row.amount,
+            time_billed_seconds: row.time_billed_seconds,
+            endpoint_id: row.endpoint_id,
+            gpu_type_id: row.gpu_type_id,
+            instance_id: row.instance_id,
+        }
+    }
+}
+
+impl From<billing_query::BillingQueryMyselfBillingStorage> for StorageBilling {
+    fn from(row: billing_query::BillingQueryMyselfBillingStorage) -> Self {
+        Self {
+            time: row.time,
+            amount: row.amount,
+            disk_space_billed_gb: row.disk_space_billed_gb,
+            network_storage_disk_space_billed_gb: row.network_storage_disk_space_billed_gb,
+            network_storage_amount: row.network_storage_amount,
+            gpu_disk_space_billed_gb: row.gpu_disk_space_billed_gb,
+            gpu_storage_amount: row.gpu_storage_amount,
+            cpu_disk_space_billed_gb: row.cpu_disk_space_billed_gb,
+            cpu_storage_amount: row.cpu_storage_amount,
+            sls_disk_space_billed_gb: row.sls_disk_space_billed_gb,
+            sls_storage_amount: row.sls_storage_amount,
+        }
+    }
+}
+
+impl From<billing_query::BillingQueryMyselfBillingSummary> for SummaryBilling {
+    fn from(row: billing_query::BillingQueryMyselfBillingSummary) -> Self {
+        Self {
+            time: row.time,
+            gpu_cloud_amount: row.gpu_cloud_amount,
+            cpu_cloud_amount: row.cpu_cloud_amount,
+            serverless_amount: row.serverless_amount,
+            storage_amount: row.storage_amount,
+            runpod_endpoint_amount: row.runpod_endpoint_amount,
+        }
+    }
+}
+
+impl From<billing_query::ClientCreditChargeType> for ChargeType {
+    fn from(charge_type: billing_query::ClientCreditChargeType) -> Self {
+        match charge_type {
+            billing_query::ClientCreditChargeType::CHARGE_SERVERLESS => ChargeType::Serverless,
+            billing_query::ClientCreditChargeType::CHARGE_POD => ChargeType::Pod,
+            billing_query::ClientCreditChargeType::CHARGE_API => ChargeType::Api,
+            billing_query::ClientCreditChargeType::CHARGE_STORAGE => ChargeType::Storage,
+            billing_query::ClientCreditChargeType::CHARGE_SAVINGS_PLAN => ChargeType::SavingsPlan,
+            other => {
+                error! {"Unknown charge type: {:?}", other};
+                ChargeType::Pod
+            }
+        }
+    }
+}
+
+impl From<billing_query::BillingQueryMyselfDailyCharges> for DailyCharge {
+    fn from(charge: billing_query::BillingQueryMyselfDailyCharges) -> Self {
+        Self {
+            amount: charge.amount,
+            updated_at: charge.updated_at,
+            disk_charges: charge.disk_charges,
+            pod_charges: charge.pod_charges,
+            api_charges: charge.api_charges,
+            serverless_charges: charge.serverless_charges,
+            charge_type: charge.type_.map(Into::into),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datetime::parse_datetime;
+
+    fn gpu(gpu: &str, time: &str, amount: f64) -> GpuCloudBilling {
+        GpuCloudBilling {
+            gpu_type_id: Some(gpu.into()),
+            time: Some(parse_datetime(time).unwrap()),
+            time_billed_seconds: Some(3600),
+            amount: Some(amount),
+        }
+    }
+
+    #[test]
+    fn groups_gpu_spend_by_period_and_type() {
+        let report = BillingReport {
+            gpu_cloud: vec![
+                gpu("NVIDIA A100", "2026-08-31T00:00:00Z", 9.0),
+                gpu("NVIDIA A100", "2026-09-01T00:00:00Z", 1.0),
+                gpu("NVIDIA A100", "2026-09-01T00:00:00Z", 2.0),
+                gpu("NVIDIA H100", "2026-09-01T00:00:00Z", 4.0),
+            ],
+            ..Default::default()
+        };
+        let since = parse_datetime("2026-09-01").unwrap();
+        let rows = report.rows(BillingGroupBy::Gpu, Some(&since));
+        assert_eq!(
+            rows,
+            vec![
+                CostRow {
+                    period: parse_datetime("2026-09-01T00:00:00Z").unwrap(),
+                    group: "NVIDIA A100".into(),
+                    amount: 3.0,
+                },
+                CostRow {
+                    period: parse_datetime("2026-09-01T00:00:00Z").unwrap(),
+                    group: "NVIDIA H100".into(),
+                    amount: 4.0,
+                },
+            ]
+        );
+    }
+}
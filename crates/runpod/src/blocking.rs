@@ -0,0 +1,163 @@
+//! A synchronous [`RunpodClient`] for scripts and tools without an async runtime.
+//!
+//! Each method runs the corresponding async method of [`crate::RunpodClient`] to
+//! completion, with requests sent by [`reqwest::blocking`], so queries, error handling,
+//! logging and metrics are the same for both clients. No tokio runtime is needed, and as
+//! with `reqwest::blocking`, the client must not be used from within one.
+//!
+//! ```no_run
+//! # fn example() -> runpod::Result<()> {
+//! let client = runpod::blocking::RunpodClient::from_config()?;
+//! for pod in client.list_pods()? {
+//!     println!("{} {}", pod.id, pod.name);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::metrics::MetricsHook;
+use crate::transport::{Transport, TransportRequest, TransportResponse};
+use crate::types::{GpuOffer, Pod, PodStatus, SaveTemplateSpec, SpawnPodSpec, Template};
+use futures::executor::block_on;
+use futures::future::BoxFuture;
+use reqwest::blocking::Client as ReqwestClient;
+use std::sync::Arc;
+
+/// Sends requests with `reqwest::blocking`. The returned future is already complete, so
+/// it can be driven without a runtime.
+#[derive(Debug, Clone)]
+struct BlockingTransport {
+    client: ReqwestClient,
+}
+
+impl Transport for BlockingTransport {
+    fn send(&self, request: TransportRequest) -> BoxFuture<'_, Result<TransportResponse>> {
+        let response = self
+            .client
+            .post(request.url)
+            .headers(request.headers)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(request.body)
+            .send()
+            .and_then(|response| {
+                let status = response.status();
+                Ok(TransportResponse {
+                    status,
+                    body: response.bytes()?.to_vec(),
+                })
+            })
+            .map_err(Into::into);
+        Box::pin(async move { response })
+    }
+}
+
+#[derive(Clone)]
+pub struct RunpodClient {
+    inner: crate::RunpodClient,
+}
+
+impl RunpodClient {
+    pub fn from_config() -> Result<Self> {
+        let config = Config::try_from_env()?;
+        Ok(Self::new(config))
+    }
+
+    pub fn new(config: Config) -> Self {
+        let client = ReqwestClient::builder()
+            .user_agent("graphql-rust/0.10.0")
+            .build()
+            .expect("Failed to create HTTP client");
+        Self::with_client(config, client)
+    }
+
+    /// Use a preconfigured `reqwest` blocking client, e.g. with a proxy or timeouts.
+    pub fn with_client(config: Config, client: ReqwestClient) -> Self {
+        Self {
+            inner: crate::RunpodClient::from_parts(config, Arc::new(BlockingTransport { client })),
+        }
+    }
+
+    /// See [`crate::RunpodClient::with_body_logging`].
+    pub fn with_body_logging(self, log_bodies: bool) -> Self {
+        Self {
+            inner: self.inner.with_body_logging(log_bodies),
+        }
+    }
+
+    /// See [`crate::RunpodClient::with_metrics`].
+    pub fn with_metrics(self, hook: impl MetricsHook + 'static) -> Self {
+        Self {
+            inner: self.inner.with_metrics(hook),
+        }
+    }
+
+    pub fn list_pods(&self) -> Result<Vec<Pod>> {
+        block_on(self.inner.list_pods())
+    }
+
+    pub fn get_pod(&self, pod_id: &str) -> Result<Option<Pod>> {
+        block_on(self.inner.get_pod(pod_id))
+    }
+
+    pub fn spawn_pod(&self, spec: SpawnPodSpec) -> Result<String> {
+        block_on(self.inner.spawn_pod(spec))
+    }
+
+    pub fn stop_pod(&self, pod_id: &str) -> Result<PodStatus> {
+        block_on(self.inner.stop_pod(pod_id))
+    }
+
+    pub fn terminate_pod(&self, pod_id: &str) -> Result<()> {
+        block_on(self.inner.terminate_pod(pod_id))
+    }
+
+    /// See [`crate::RunpodClient::list_gpus`].
+    pub fn list_gpus(&self, secure_cloud: Option<bool>) -> Result<Vec<GpuOffer>> {
+        block_on(self.inner.list_gpus(secure_cloud))
+    }
+
+    pub fn get_templates(&self) -> Result<Vec<Template>> {
+        block_on(self.inner.get_templates())
+    }
+
+    pub fn save_template(&self, spec: SaveTemplateSpec) -> Result<Template> {
+        block_on(self.inner.save_template(spec))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{FakeGpu, FakeRunpod, FAKE_API_KEY};
+    use crate::RunpodError;
+
+    #[tokio::test]
+    async fn blocking_client_against_fake_api() {
+        let fake = FakeRunpod::start().await;
+        fake.add_gpu(FakeGpu::new("RTX 4090", 0.69, 1));
+        let config = Config::new(FAKE_API_KEY, fake.url());
+
+        // reqwest::blocking must not run on the runtime's own threads.
+        let (gpus, pod, missing) = tokio::task::spawn_blocking(move || {
+            let client = RunpodClient::new(config);
+            let gpus = client.list_gpus(Some(true)).unwrap();
+            let spec = SpawnPodSpec::builder()
+                .name("exp")
+                .gpu_type_id("RTX 4090")
+                .template_id("tpl")
+                .build();
+            let id = client.spawn_pod(spec).unwrap();
+            let pod = client.get_pod(&id).unwrap().unwrap();
+            client.terminate_pod(&id).unwrap();
+            (gpus, pod, client.stop_pod(&id))
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(gpus[0].id, "RTX 4090");
+        assert_eq!(pod.name, "exp");
+        assert!(matches!(missing, Err(RunpodError::PodNotFound(_))));
+    }
+}
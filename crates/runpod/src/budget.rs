@@ -0,0 +1,233 @@
+use crate::error::Result;
+use crate::savings::SavingsPlanSpec;
+use crate::types::{AccountSummary, GpuOffer, SpawnPodSpec};
+use crate::{RunpodClient, RunpodError};
+use tracing::info;
+use typed_builder::TypedBuilder;
+
+/// Limits checked before a pod is spawned.
+#[derive(Debug, Clone, Default, TypedBuilder)]
+pub struct BudgetLimits {
+    /// Maximum total spend per hour, including the new pod.
+    #[builder(default, setter(strip_option))]
+    pub max_spend_per_hr: Option<f64>,
+    /// Minimum number of hours the balance must last at the new spend rate.
+    #[builder(default, setter(strip_option))]
+    pub min_runway_hours: Option<f64>,
+}
+
+impl BudgetLimits {
+    /// Check whether adding `projected_cost_per_hr` to the account's current spend stays
+    /// within these limits. A `savings_plan` prepays the pod's hours: its upfront cost is
+    /// taken out of the balance before the runway is worked out, and the pod's hourly rate
+    /// is not charged on top.
+    pub fn check(
+        &self,
+        summary: &AccountSummary,
+        projected_cost_per_hr: f64,
+        savings_plan: Option<&SavingsPlanSpec>,
+    ) -> Result<()> {
+        let (projected_cost_per_hr, upfront_cost) = match savings_plan {
+            Some(plan) => (0.0, plan.upfront_cost),
+            None => (projected_cost_per_hr, 0.0),
+        };
+        let current = summary.current_spend_per_hr.unwrap_or(0.0);
+        let total = current + projected_cost_per_hr;
+
+        if let Some(cap) = self.max_spend_per_hr {
+            if total > cap {
+                return Err(RunpodError::BudgetExceeded(format!(
+                    "spend would rise to ${total:.3}/hr (currently ${current:.3}/hr), cap is ${cap:.3}/hr"
+                )));
+            }
+        }
+
+        if let Some(min_hours) = self.min_runway_hours {
+            let balance = summary.client_balance.unwrap_or(0.0) - upfront_cost;
+            if upfront_cost > 0.0 && balance < 0.0 {
+                return Err(RunpodError::BudgetExceeded(format!(
+                    "upfront cost of ${upfront_cost:.2} is more than the balance of ${:.2}",
+                    balance + upfront_cost
+                )));
+            }
+            if total > 0.0 && balance / total < min_hours {
+                return Err(RunpodError::BudgetExceeded(format!(
+                    "balance of ${balance:.2} would last {:.1}h at ${total:.3}/hr, need at least {min_hours:.1}h",
+                    balance / total
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Estimated hourly cost of `gpu_count` GPUs of the given offer.
+///
+/// Spot pods are costed at `bid_per_gpu` when given, otherwise at the cheapest spot price.
+/// On-demand pods use the lowest uninterruptable price, falling back to the cheaper of the
+/// secure and community prices.
+pub fn estimate_cost_per_hr(
+    offer: &GpuOffer,
+    gpu_count: i64,
+    spot: bool,
+    bid_per_gpu: Option<f64>,
+) -> Option<f64> {
+    let cheapest = |a: Option<f64>, b: Option<f64>| match (a, b) {
+        (Some(x), Some(y)) => Some(x.min(y)),
+        (x, y) => x.or(y),
+    };
+    let per_gpu = if spot {
+        bid_per_gpu.or_else(|| cheapest(offer.secure_spot_price, offer.community_spot_price))
+    } else {
+        offer
+            .lowest_price
+            .as_ref()
+            .and_then(|p| p.uninterruptable_price)
+            .or_else(|| cheapest(offer.secure_price, offer.community_price))
+    }?;
+    Some(per_gpu * gpu_count as f64)
+}
+
+/// Wraps a [`RunpodClient`] and refuses to spawn pods that would break the configured
+/// [`BudgetLimits`].
+#[derive(Debug, Clone)]
+pub struct BudgetGuard {
+    client: RunpodClient,
+    limits: BudgetLimits,
+}
+
+impl BudgetGuard {
+    pub fn new(client: RunpodClient, limits: BudgetLimits) -> Self {
+        Self { client, limits }
+    }
+
+    pub fn client(&self) -> &RunpodClient {
+        &self.client
+    }
+
+    /// Check the limits for a prospective pod without spawning it, including the upfront
+    /// cost of a savings plan bought with it.
+    pub async fn check_spawn(
+        &self,
+        gpu_type_id: &str,
+        gpu_count: i64,
+        spot: bool,
+        bid_per_gpu: Option<f64>,
+        savings_plan: Option<&SavingsPlanSpec>,
+    ) -> Result<()> {
+        if self.limits.max_spend_per_hr.is_none() && self.limits.min_runway_hours.is_none() {
+            return Ok(());
+        }
+        let offer = self
+            .client
+            .list_gpus(None)
+            .await?
+            .into_iter()
+            .find(|gpu| gpu.id == gpu_type_id)
+            .ok_or_else(|| RunpodError::NotFound(format!("GPU type {gpu_type_id}")))?;
+        let projected =
+            estimate_cost_per_hr(&offer, gpu_count, spot, bid_per_gpu).ok_or_else(|| {
+                RunpodError::BudgetExceeded(format!(
+                    "no price available for GPU type {gpu_type_id}"
+                ))
+            })?;
+        let summary = self.client.account_summary().await?;
+        self.limits.check(&summary, projected, savings_plan)?;
+        info!(
+            "Budget check passed for {gpu_count}x {gpu_type_id} at ${projected:.3}/hr (current ${:.3}/hr)",
+            summary.current_spend_per_hr.unwrap_or(0.0)
+        );
+        Ok(())
+    }
+
+    /// Same as [`RunpodClient::spawn_pod`], but fails with [`RunpodError::BudgetExceeded`]
+    /// if the new pod would break the limits.
+    pub async fn spawn_pod(&self, spec: SpawnPodSpec) -> Result<String> {
+        self.check_spawn(
+            &spec.gpu_type_id,
+            spec.gpu_count,
+            spec.spot,
+            spec.bid_per_gpu,
+            spec.savings_plan.as_ref(),
+        )
+        .await?;
+        self.client.spawn_pod(spec).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::savings::SavingsPlanLength;
+
+    fn summary(spend: f64, balance: f64) -> AccountSummary {
+        AccountSummary {
+            current_spend_per_hr: Some(spend),
+            client_balance: Some(balance),
+            spend_limit: None,
+            under_balance: Some(false),
+            credit_alert_threshold: None,
+            spend_details: None,
+        }
+    }
+
+    #[test]
+    fn rejects_spend_over_cap() {
+        let limits = BudgetLimits::builder().max_spend_per_hr(5.0).build();
+        assert!(limits.check(&summary(3.0, 100.0), 1.5, None).is_ok());
+        assert!(matches!(
+            limits.check(&summary(3.0, 100.0), 2.5, None),
+            Err(RunpodError::BudgetExceeded(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_short_runway() {
+        let limits = BudgetLimits::builder().min_runway_hours(24.0).build();
+        assert!(limits.check(&summary(1.0, 100.0), 2.0, None).is_ok());
+        assert!(matches!(
+            limits.check(&summary(1.0, 100.0), 4.0, None),
+            Err(RunpodError::BudgetExceeded(_))
+        ));
+    }
+
+    fn plan(upfront_cost: f64) -> SavingsPlanSpec {
+        SavingsPlanSpec {
+            plan_length: SavingsPlanLength::OneMonth,
+            upfront_cost,
+            accept_unverified: false,
+        }
+    }
+
+    #[test]
+    fn counts_upfront_cost_against_runway() {
+        let limits = BudgetLimits::builder().min_runway_hours(24.0).build();
+        assert!(limits
+            .check(&summary(1.0, 1000.0), 2.0, Some(&plan(900.0)))
+            .is_ok());
+        // $980 upfront leaves $20, which lasts under 24h at the existing $1/hr.
+        assert!(matches!(
+            limits.check(&summary(1.0, 1000.0), 2.0, Some(&plan(980.0))),
+            Err(RunpodError::BudgetExceeded(_))
+        ));
+        assert!(matches!(
+            limits.check(&summary(0.0, 1000.0), 0.0, Some(&plan(1500.0))),
+            Err(RunpodError::BudgetExceeded(_))
+        ));
+    }
+
+    #[test]
+    fn savings_plan_hours_are_not_charged_twice() {
+        let limits = BudgetLimits::builder()
+            .min_runway_hours(24.0)
+            .max_spend_per_hr(5.0)
+            .build();
+        // On demand, $200 lasts 20h at $10/hr and breaks the cap.
+        assert!(limits.check(&summary(0.0, 200.0), 10.0, None).is_err());
+        // With a $100 plan, the $100 left lasts 100h at the existing $1/hr.
+        assert!(limits
+            .check(&summary(1.0, 200.0), 10.0, Some(&plan(100.0)))
+            .is_ok());
+    }
+}
@@ -0,0 +1,275 @@
+//! Record and replay API traffic, VCR style.
+//!
+//! [`RecordingTransport`] wraps another [`Transport`] and writes every request and response
+//! it sees to a JSON cassette file. [`ReplayTransport`] answers requests from such a
+//! cassette without any network access, in the order they were recorded. Use it to pin
+//! real responses as regression fixtures:
+//!
+//! ```no_run
+//! # async fn example() -> runpod::Result<()> {
+//! use runpod::cassette::{RecordingTransport, ReplayTransport};
+//! use runpod::transport::ReqwestTransport;
+//! use runpod::RunpodClient;
+//!
+//! // Once, against the real API:
+//! let client = RunpodClient::from_config()?
+//!     .with_transport(RecordingTransport::new(ReqwestTransport::new(), "gpus.json"));
+//! client.list_gpus(Some(true)).await?;
+//!
+//! // In tests:
+//! let client = RunpodClient::from_config()?.with_transport(ReplayTransport::load("gpus.json")?);
+//! client.list_gpus(Some(true)).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Headers, including the API key, are never recorded. Variables and JSON response bodies
+//! are scrubbed with [`redact::scrub`], which replaces the strings under sensitive fields
+//! such as `env` and `password` but keeps the document's shape, so recorded responses
+//! still deserialize.
+
+use crate::error::Result;
+use crate::redact;
+use crate::transport::{Transport, TransportRequest, TransportResponse};
+use crate::RunpodError;
+use futures::future::BoxFuture;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// One recorded request and its response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub operation: String,
+    /// The request's GraphQL variables, scrubbed.
+    #[serde(default)]
+    pub variables: Value,
+    pub status: u16,
+    /// The response body, scrubbed, if it was JSON.
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub response: Value,
+    /// The response body verbatim, if it was not JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+impl Interaction {
+    fn body(&self) -> Vec<u8> {
+        match &self.text {
+            Some(text) => text.clone().into_bytes(),
+            None => self.response.to_string().into_bytes(),
+        }
+    }
+}
+
+/// The operation name and scrubbed variables of a serialized request.
+fn request_key(request: &TransportRequest) -> (String, Value) {
+    let mut body: Value = serde_json::from_slice(&request.body).unwrap_or_default();
+    let mut variables = body["variables"].take();
+    redact::scrub(&mut variables);
+    (request.operation.to_string(), variables)
+}
+
+/// A sequence of recorded interactions, stored as pretty-printed JSON.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let file = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&file)?)
+    }
+
+    /// Write the cassette, creating parent directories as needed.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+}
+
+/// Passes requests through to another transport and records them to a cassette file.
+///
+/// The file is replaced on the first request and rewritten after every response, so it is
+/// complete even if the process stops early.
+pub struct RecordingTransport<T> {
+    inner: T,
+    path: PathBuf,
+    cassette: Mutex<Cassette>,
+}
+
+impl<T: Transport> RecordingTransport<T> {
+    pub fn new(inner: T, path: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            path: path.into(),
+            cassette: Mutex::new(Cassette::default()),
+        }
+    }
+}
+
+impl<T: Transport> Transport for RecordingTransport<T> {
+    fn send(&self, request: TransportRequest) -> BoxFuture<'_, Result<TransportResponse>> {
+        Box::pin(async move {
+            let (operation, variables) = request_key(&request);
+            let response = self.inner.send(request).await?;
+
+            let (response_json, text) = match serde_json::from_slice::<Value>(&response.body) {
+                Ok(mut body) => {
+                    redact::scrub(&mut body);
+                    (body, None)
+                }
+                Err(_) => (
+                    Value::Null,
+                    Some(String::from_utf8_lossy(&response.body).into_owned()),
+                ),
+            };
+            let cassette = {
+                let mut cassette = self.cassette.lock().expect("cassette lock poisoned");
+                cassette.interactions.push(Interaction {
+                    operation,
+                    variables,
+                    status: response.status.as_u16(),
+                    response: response_json,
+                    text,
+                });
+                cassette.clone()
+            };
+            cassette.save(&self.path)?;
+            Ok(response)
+        })
+    }
+}
+
+/// Answers requests from a cassette, in the order they were recorded.
+///
+/// Each request must have the same operation and (scrubbed) variables as the next recorded
+/// interaction, otherwise it fails with [`RunpodError::InvalidInput`], as does any request
+/// once the cassette is used up.
+pub struct ReplayTransport {
+    interactions: Mutex<VecDeque<Interaction>>,
+    match_variables: bool,
+}
+
+impl ReplayTransport {
+    pub fn new(cassette: Cassette) -> Self {
+        Self {
+            interactions: Mutex::new(cassette.interactions.into()),
+            match_variables: true,
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::new(Cassette::load(path)?))
+    }
+
+    /// Match requests on the operation name only, for requests whose variables change
+    /// between runs, such as deadlines computed from the current time.
+    pub fn ignore_variables(mut self) -> Self {
+        self.match_variables = false;
+        self
+    }
+
+    /// Interactions not replayed yet.
+    pub fn remaining(&self) -> usize {
+        self.interactions
+            .lock()
+            .expect("cassette lock poisoned")
+            .len()
+    }
+
+    fn next(&self, request: &TransportRequest) -> Result<TransportResponse> {
+        let (operation, variables) = request_key(request);
+        let mut interactions = self.interactions.lock().expect("cassette lock poisoned");
+        let Some(next) = interactions.front() else {
+            return Err(RunpodError::InvalidInput(format!(
+                "cassette has no more interactions, got {operation}"
+            )));
+        };
+        if next.operation != operation {
+            return Err(RunpodError::InvalidInput(format!(
+                "cassette expected {}, got {operation}",
+                next.operation
+            )));
+        }
+        if self.match_variables && next.variables != variables {
+            return Err(RunpodError::InvalidInput(format!(
+                "cassette expected {operation} with variables {}, got {variables}",
+                next.variables
+            )));
+        }
+        let next = interactions.pop_front().expect("checked above");
+        Ok(TransportResponse {
+            status: StatusCode::from_u16(next.status).unwrap_or(StatusCode::OK),
+            body: next.body(),
+        })
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn send(&self, request: TransportRequest) -> BoxFuture<'_, Result<TransportResponse>> {
+        let response = self.next(&request);
+        Box::pin(async move { response })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{FakeGpu, FakeRunpod};
+    use crate::transport::ReqwestTransport;
+    use crate::types::{EnvironmentVariable, SpawnPodSpec};
+    use crate::RunpodClient;
+
+    #[tokio::test]
+    async fn records_scrubbed_and_replays() {
+        let path = std::env::temp_dir().join(format!(
+            "runpod-cassette-{}-{:?}.json",
+            std::process::id(),
+            std::time::SystemTime::now()
+        ));
+        let fake = FakeRunpod::start().await;
+        fake.add_gpu(FakeGpu::new("RTX 4090", 0.69, 1));
+        let spec = SpawnPodSpec::builder()
+            .name("exp")
+            .gpu_type_id("RTX 4090")
+            .template_id("tpl")
+            .env(vec![EnvironmentVariable::new("HF_TOKEN", "hf_secret")])
+            .build();
+
+        let recording = fake
+            .client()
+            .with_transport(RecordingTransport::new(ReqwestTransport::new(), &path));
+        let id = recording.spawn_pod(spec.clone()).await.unwrap();
+        let recorded = recording.list_pods().await.unwrap();
+        drop(fake);
+
+        let file = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(!file.contains("hf_secret"));
+        assert!(!file.contains(crate::testing::FAKE_API_KEY));
+
+        let replay = ReplayTransport::new(serde_json::from_str(&file).unwrap());
+        let client = RunpodClient::new(crate::config::Config::new(
+            "unused",
+            "http://localhost/graphql",
+        ))
+        .with_transport(replay);
+        assert_eq!(client.spawn_pod(spec).await.unwrap(), id);
+        let replayed = client.list_pods().await.unwrap();
+        assert_eq!(replayed[0].id, recorded[0].id);
+        assert_eq!(replayed[0].env, vec![redact::REDACTED.to_string()]);
+        assert!(matches!(
+            client.list_pods().await,
+            Err(RunpodError::InvalidInput(_))
+        ));
+    }
+}
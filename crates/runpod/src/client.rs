@@ -1,20 +1,46 @@
+use crate::audit::{self, AuditLog, AuditLogFilter};
+use crate::billing::{BillingGranularity, BillingReport};
 use crate::config::Config;
+use crate::error::GraphQLErrors;
 use crate::gql::bid_spot::CloudTypeEnum;
 use crate::gql::gpu_types::{GpuLowestPriceInput, GpuTypeFilter};
+use crate::host::{
+    self, EarningsRange, HostMachine, MachineEarning, MachineSummary, MaintenanceWindow,
+};
+use crate::metrics::{MetricsHook, RequestMetrics};
+use crate::redact;
+use crate::savings::SavingsPlan;
+use crate::schema;
+use crate::teams::{Team, TeamRoster};
+use crate::transport::{ReqwestTransport, Transport, TransportRequest};
+use crate::watch::{PodEvent, PodWatcher};
 use crate::RunpodError;
 use crate::{error::Result, gql::*, types::*};
-use graphql_client::GraphQLQuery;
-use graphql_client::Response;
-use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
-use reqwest::{Client as ReqwestClient, Url};
+use futures::stream::{self, Stream, StreamExt};
+use graphql_client::{GraphQLQuery, QueryBody, Response};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use reqwest::Url;
 use serde::{de::DeserializeOwned, Serialize};
-use tracing::{debug, error, info};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::field::Empty;
+use tracing::{debug, debug_span, error, info, warn, Instrument};
 
 #[derive(Clone)]
 pub struct RunpodClient {
-    client: ReqwestClient,
+    transport: Arc<dyn Transport>,
     apikey: String,
+    auth_header: HeaderValue,
     apiurl: Url,
+    log_bodies: bool,
+    metrics: Option<Arc<dyn MetricsHook>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum BodyLogging {
+    Full,
+    Redacted,
+    Off,
 }
 
 impl std::fmt::Debug for RunpodClient {
@@ -39,38 +65,166 @@ impl RunpodClient {
         Ok(Self::new(config))
     }
 
-    pub fn new(Config { apikey, apiurl }: Config) -> Self {
-        let client = ReqwestClient::builder()
-            .user_agent("graphql-rust/0.10.0")
-            .default_headers(HeaderMap::from_iter([(
-                HeaderName::from_static("authorization"),
-                HeaderValue::from_str(&format!("Bearer {}", apikey))
-                    .expect("invalid api key header"),
-            )]))
-            .build()
-            .expect("Failed to create HTTP client");
+    pub fn new(config: Config) -> Self {
+        Self::from_parts(config, Arc::new(ReqwestTransport::new()))
+    }
+
+    /// Like [`Self::new`], but without building the default transport first.
+    pub(crate) fn from_parts(
+        Config {
+            apikey,
+            apiurl,
+            log_bodies,
+            ..
+        }: Config,
+        transport: Arc<dyn Transport>,
+    ) -> Self {
+        let mut auth_header =
+            HeaderValue::from_str(&format!("Bearer {}", apikey)).expect("invalid api key header");
+        auth_header.set_sensitive(true);
         Self {
-            client,
+            transport,
             apikey,
+            auth_header,
             apiurl: apiurl.parse().expect("invalid api url in config"),
+            log_bodies,
+            metrics: None,
         }
     }
 
-    async fn request<Req, Res>(&self, params: &Req) -> Result<Res>
+    /// Log full, unredacted request and response bodies at debug level. Off by default;
+    /// otherwise only the operation name, redacted variables and response size are logged.
+    pub fn with_body_logging(mut self, log_bodies: bool) -> Self {
+        self.log_bodies = log_bodies;
+        self
+    }
+
+    /// Send requests through `transport` instead of the default [`ReqwestTransport`].
+    pub fn with_transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    /// Report every request to `hook`, in addition to the `graphql` tracing span each
+    /// request runs in.
+    pub fn with_metrics(mut self, hook: impl MetricsHook + 'static) -> Self {
+        self.metrics = Some(Arc::new(hook));
+        self
+    }
+
+    async fn request<Vars, Res>(&self, params: &QueryBody<Vars>) -> Result<Res>
     where
-        Req: Serialize + std::fmt::Debug,
+        Vars: Serialize,
         Res: DeserializeOwned,
     {
-        debug!(
-            "Making request to {} with params: {:?}",
-            &self.apiurl, params
+        let logging = if self.log_bodies {
+            BodyLogging::Full
+        } else {
+            BodyLogging::Redacted
+        };
+        self.execute(params, logging).await
+    }
+
+    /// Like [`Self::request`], but never logs the request variables or response body, even
+    /// with body logging enabled. Used for requests that carry secret values.
+    async fn request_sensitive<Vars, Res>(&self, params: &QueryBody<Vars>) -> Result<Res>
+    where
+        Vars: Serialize,
+        Res: DeserializeOwned,
+    {
+        self.execute(params, BodyLogging::Off).await
+    }
+
+    async fn execute<Vars, Res>(
+        &self,
+        params: &QueryBody<Vars>,
+        logging: BodyLogging,
+    ) -> Result<Res>
+    where
+        Vars: Serialize,
+        Res: DeserializeOwned,
+    {
+        let operation = params.operation_name;
+        let span = debug_span!(
+            "graphql",
+            operation,
+            latency_ms = Empty,
+            http_status = Empty,
+            graphql_errors = Empty,
         );
-        let request = self.client.post(self.apiurl.clone()).json(&params);
+        let mut metrics = RequestMetrics {
+            operation,
+            latency: Duration::ZERO,
+            http_status: None,
+            graphql_errors: 0,
+            success: false,
+        };
 
-        let response = request.send().await?;
-        let status = response.status();
-        let body = response.bytes().await?;
-        debug!("Response body: {}", String::from_utf8_lossy(&body));
+        let start = Instant::now();
+        let result = async {
+            let params = serde_json::to_value(params)?;
+            self.execute_inner(params, logging, &mut metrics).await
+        }
+        .instrument(span.clone())
+        .await;
+        metrics.latency = start.elapsed();
+        metrics.success = result.is_ok();
+
+        span.record("latency_ms", metrics.latency.as_millis() as u64);
+        if let Some(status) = metrics.http_status {
+            span.record("http_status", status);
+        }
+        span.record("graphql_errors", metrics.graphql_errors);
+        span.in_scope(|| debug!(success = metrics.success, "Request finished"));
+        if let Some(hook) = &self.metrics {
+            hook.record(&metrics);
+        }
+        result
+    }
+
+    async fn execute_inner<Res>(
+        &self,
+        params: serde_json::Value,
+        logging: BodyLogging,
+        metrics: &mut RequestMetrics,
+    ) -> Result<Res>
+    where
+        Res: DeserializeOwned,
+    {
+        match logging {
+            BodyLogging::Full => debug!("Request to {}: {}", &self.apiurl, params),
+            BodyLogging::Redacted => debug!(
+                "Request to {} with variables: {}",
+                &self.apiurl,
+                params
+                    .get("variables")
+                    .map(redact::redacted)
+                    .unwrap_or_default()
+            ),
+            BodyLogging::Off => debug!("Request to {}", &self.apiurl),
+        }
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, self.auth_header.clone());
+        let request = TransportRequest {
+            url: self.apiurl.clone(),
+            operation: metrics.operation,
+            headers,
+            body: serde_json::to_vec(&params)?,
+        };
+
+        let response = self.transport.send(request).await?;
+        let status = response.status;
+        metrics.http_status = Some(status.as_u16());
+        let body = response.body;
+        match logging {
+            BodyLogging::Full => debug!("Response body: {}", String::from_utf8_lossy(&body)),
+            _ => debug!("Response {} ({} bytes)", status, body.len()),
+        }
+        let loggable_body = || match logging {
+            BodyLogging::Full => String::from_utf8_lossy(&body).into_owned(),
+            BodyLogging::Redacted => redact::redact_body(&body),
+            BodyLogging::Off => "<not logged>".to_string(),
+        };
 
         // First check if it's a non-200 status code
         if !status.is_success() {
@@ -93,52 +247,56 @@ impl RunpodClient {
                 _ => RunpodError::ServerError(format!(
                     "Server returned {} - {}",
                     status,
-                    String::from_utf8_lossy(&body)
+                    loggable_body()
                 )),
             });
         }
 
         let jd = &mut serde_json::Deserializer::from_slice(&body);
-        let response: Response<Res> = match serde_path_to_error::deserialize(jd) {
+        let response: Response<serde_json::Value> = match serde_path_to_error::deserialize(jd) {
             Ok(response) => response,
             Err(err) => {
-                let json_str = String::from_utf8_lossy(&body);
                 error!(
                     "Failed to deserialize response at path {}: {}. Raw response: {}",
                     err.path(),
                     err,
-                    json_str
+                    loggable_body()
                 );
                 return Err(RunpodError::DeserializationError(err.into_inner()));
             }
         };
+        let data = response.data.filter(|data| !data.is_null());
 
-        match response {
-            Response {
-                data: Some(res),
-                errors: None,
-                ..
-            } => Ok(res),
-            Response {
-                errors: Some(errors),
-                ..
-            } => {
-                error!("GraphQL errors: {:#?}", errors);
-                Err(RunpodError::GraphQLError(
-                    errors
-                        .into_iter()
-                        .next()
-                        .map_or_else(|| "Unknown GraphQL error".to_string(), |e| e.message),
-                ))
-            }
-            _ => {
-                error!("Response is missing both data and errors");
-                Err(RunpodError::ServerError(format!(
-                    "Invalid response format: {}",
-                    String::from_utf8_lossy(&body)
-                )))
+        if let Some(errors) = response.errors.filter(|errors| !errors.is_empty()) {
+            metrics.graphql_errors = errors.len();
+            error!("GraphQL errors: {:#?}", errors);
+            return Err(GraphQLErrors {
+                operation: metrics.operation,
+                errors,
+                data: match logging {
+                    BodyLogging::Off => None,
+                    _ => data,
+                },
             }
+            .into());
         }
+
+        let Some(data) = data else {
+            error!("Response is missing both data and errors");
+            return Err(RunpodError::ServerError(format!(
+                "Invalid response format: {}",
+                loggable_body()
+            )));
+        };
+        serde_path_to_error::deserialize(data).map_err(|err| {
+            error!(
+                "Failed to deserialize response at path data.{}: {}. Raw response: {}",
+                err.path(),
+                err,
+                loggable_body()
+            );
+            RunpodError::DeserializationError(err.into_inner())
+        })
     }
 
     pub async fn list_pods(&self) -> Result<Vec<Pod>> {
@@ -148,6 +306,30 @@ impl RunpodClient {
         Ok(response.myself.pods.into_iter().map(Into::into).collect())
     }
 
+    /// Poll [`Self::list_pods`] every `interval` and yield what changed between polls.
+    ///
+    /// The first poll reports every existing pod as [`PodEvent::Added`]. Failed polls are
+    /// reported as [`PodEvent::PollFailed`] and do not end the stream.
+    pub fn watch_pods(&self, interval: Duration) -> impl Stream<Item = PodEvent> + '_ {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        stream::unfold(
+            (ticker, PodWatcher::new()),
+            move |(mut ticker, mut watcher)| async move {
+                ticker.tick().await;
+                let events = match self.list_pods().await {
+                    Ok(pods) => watcher.diff(pods),
+                    Err(e) => {
+                        warn!("Failed to list pods: {}", e);
+                        vec![PodEvent::PollFailed(e.to_string())]
+                    }
+                };
+                Some((stream::iter(events), (ticker, watcher)))
+            },
+        )
+        .flatten()
+    }
+
     pub async fn stop_pod(&self, pod_id: &str) -> Result<PodStatus> {
         let variables = stop_pod::Variables {
             input: stop_pod::PodStopInput {
@@ -249,25 +431,261 @@ impl RunpodClient {
         }
     }
 
-    pub async fn spawn_pod(
+    /// List the names and metadata of the account's secrets. Values are never returned.
+    pub async fn list_secrets(&self) -> Result<Vec<Secret>> {
+        let variables = list_secrets::Variables {};
+        let request_body = ListSecrets::build_query(variables);
+        let response: list_secrets::ResponseData = self.request(&request_body).await?;
+        Ok(response
+            .myself
+            .secrets
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Create a secret. The value is sent to the API but never logged.
+    pub async fn create_secret(
+        &self,
+        name: &str,
+        value: &str,
+        description: Option<&str>,
+    ) -> Result<Secret> {
+        let variables = create_secret::Variables {
+            input: create_secret::SecretCreateInput {
+                name: name.to_string(),
+                value: value.to_string(),
+                description: description.map(str::to_string),
+            },
+        };
+        let request_body = CreateSecret::build_query(variables);
+        debug!("Creating secret {}", name);
+        let response: create_secret::ResponseData = self.request_sensitive(&request_body).await?;
+        response
+            .secret_create
+            .map(Into::into)
+            .ok_or(RunpodError::EmptyResponse("Secret not created"))
+    }
+
+    /// Replace the value (and optionally the description) of an existing secret.
+    pub async fn update_secret(
+        &self,
+        id: &str,
+        value: &str,
+        description: Option<&str>,
+    ) -> Result<Secret> {
+        let variables = update_secret::Variables {
+            input: update_secret::SecretUpdateInput {
+                id: id.to_string(),
+                name: None,
+                value: Some(value.to_string()),
+                description: description.map(str::to_string),
+            },
+        };
+        let request_body = UpdateSecret::build_query(variables);
+        debug!("Updating secret {}", id);
+        let response: update_secret::ResponseData = self.request_sensitive(&request_body).await?;
+        response
+            .secret_update
+            .map(Into::into)
+            .ok_or(RunpodError::EmptyResponse("Secret not updated"))
+    }
+
+    /// Create the secret if no secret with this name exists, otherwise update its value.
+    pub async fn set_secret(
+        &self,
+        name: &str,
+        value: &str,
+        description: Option<&str>,
+    ) -> Result<Secret> {
+        let existing = self
+            .list_secrets()
+            .await?
+            .into_iter()
+            .find(|s| s.name == name);
+        match existing {
+            Some(secret) => self.update_secret(&secret.id, value, description).await,
+            None => self.create_secret(name, value, description).await,
+        }
+    }
+
+    pub async fn delete_secret(&self, id: &str) -> Result<()> {
+        let variables = delete_secret::Variables { id: id.to_string() };
+        let request_body = DeleteSecret::build_query(variables);
+        let _: delete_secret::ResponseData = self.request(&request_body).await?;
+        Ok(())
+    }
+
+    /// Check that every secret referenced by `env` exists.
+    async fn check_secret_refs(&self, env: &[EnvironmentVariable]) -> Result<()> {
+        let refs: Vec<&str> = env.iter().flat_map(|e| e.secret_refs()).collect();
+        if refs.is_empty() {
+            return Ok(());
+        }
+        let secrets = self.list_secrets().await?;
+        let missing: Vec<&str> = refs
+            .into_iter()
+            .filter(|r| !secrets.iter().any(|s| s.name == *r))
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(RunpodError::InvalidInput(format!(
+                "unknown secret(s) referenced in env: {}",
+                missing.join(", ")
+            )))
+        }
+    }
+
+    /// List the account's API keys. Only metadata is returned, never the keys themselves.
+    pub async fn list_api_keys(&self) -> Result<Vec<ApiKey>> {
+        let variables = list_api_keys::Variables {};
+        let request_body = ListApiKeys::build_query(variables);
+        let response: list_api_keys::ResponseData = self.request(&request_body).await?;
+        Ok(response
+            .myself
+            .api_keys
+            .unwrap_or_default()
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Audit log entries matching `filter`, oldest first. The API only records these for
+    /// support impersonation sessions, see [`crate::audit`].
+    ///
+    /// The API can't page through a session's entries, so this makes a single request. A
+    /// session with more entries than that page ends the stream with
+    /// [`RunpodError::IncompleteResponse`].
+    pub fn audit_logs(&self, filter: AuditLogFilter) -> impl Stream<Item = Result<AuditLog>> + '_ {
+        stream::once(async move {
+            let variables = audit_logs::Variables {};
+            let request_body = AuditLogs::build_query(variables);
+            let response: audit_logs::ResponseData = self.request(&request_body).await?;
+            Ok(audit::collect(
+                response.myself.impersonations,
+                response.myself.active_impersonation,
+            ))
+        })
+        .flat_map(move |logs: Result<Vec<Result<AuditLog>>>| {
+            let logs = match logs {
+                Ok(logs) => logs,
+                Err(e) => vec![Err(e)],
+            };
+            stream::iter(
+                logs.into_iter()
+                    .filter(|log| log.as_ref().map_or(true, |log| filter.matches(log)))
+                    .collect::<Vec<_>>(),
+            )
+        })
+    }
+
+    /// List the account's saved container registry credentials.
+    pub async fn list_registry_auths(&self) -> Result<Vec<RegistryAuth>> {
+        let variables = list_registry_auths::Variables {};
+        let request_body = ListRegistryAuths::build_query(variables);
+        let response: list_registry_auths::ResponseData = self.request(&request_body).await?;
+        Ok(response
+            .myself
+            .container_registry_creds
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .filter_map(Into::into)
+            .collect())
+    }
+
+    /// Save credentials for a private container registry. The password is sent to the API
+    /// but never logged.
+    pub async fn save_registry_auth(
         &self,
-        name: String,
-        gpu_type_id: String,
-        gpu_count: i64,
-        spot: bool,
-        bid_per_gpu: Option<f64>,
-        container_disk_in_gb: Option<i64>,
-        template: String,
-    ) -> Result<String> {
-        if spot {
+        name: &str,
+        username: &str,
+        password: &str,
+    ) -> Result<RegistryAuth> {
+        let variables = save_registry_auth::Variables {
+            input: save_registry_auth::SaveRegistryAuthInput {
+                name: name.to_string(),
+                username: username.to_string(),
+                password: password.to_string(),
+            },
+        };
+        let request_body = SaveRegistryAuth::build_query(variables);
+        debug!("Saving registry auth {}", name);
+        let response: save_registry_auth::ResponseData =
+            self.request_sensitive(&request_body).await?;
+        response
+            .save_registry_auth
+            .and_then(Into::into)
+            .ok_or(RunpodError::EmptyResponse("Registry auth not saved"))
+    }
+
+    pub async fn delete_registry_auth(&self, id: &str) -> Result<()> {
+        let variables = delete_registry_auth::Variables {
+            registry_auth_id: id.to_string(),
+        };
+        let request_body = DeleteRegistryAuth::build_query(variables);
+        let _: delete_registry_auth::ResponseData = self.request(&request_body).await?;
+        Ok(())
+    }
+
+    /// Resolve a registry auth reference to its id, looking names up in the account's
+    /// saved credentials.
+    pub async fn resolve_registry_auth(&self, auth: &RegistryAuthRef) -> Result<String> {
+        match auth {
+            RegistryAuthRef::Id(id) => Ok(id.clone()),
+            RegistryAuthRef::Name(name) => self
+                .list_registry_auths()
+                .await?
+                .into_iter()
+                .find(|a| a.name == *name)
+                .map(|a| a.id)
+                .ok_or_else(|| RunpodError::NotFound(format!("registry auth {name}"))),
+        }
+    }
+
+    async fn resolve_registry_auth_option(
+        &self,
+        auth: Option<&RegistryAuthRef>,
+    ) -> Result<Option<String>> {
+        match auth {
+            Some(auth) => self.resolve_registry_auth(auth).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn spawn_pod(&self, spec: SpawnPodSpec) -> Result<String> {
+        if spec.spot && spec.savings_plan.is_some() {
+            return Err(RunpodError::InvalidInput(
+                "savings plans are only available for on-demand pods".to_string(),
+            ));
+        }
+        if spec
+            .savings_plan
+            .as_ref()
+            .is_some_and(|plan| !plan.accept_unverified)
+        {
+            return Err(RunpodError::InvalidInput(
+                "buying a savings plan sends an unverified planLength; set accept_unverified \
+                 on the SavingsPlanSpec to opt in"
+                    .to_string(),
+            ));
+        }
+        self.check_secret_refs(&spec.env).await?;
+        let container_registry_auth_id = self
+            .resolve_registry_auth_option(spec.container_registry_auth.as_ref())
+            .await?;
+        let stop_after = spec.stop_after.as_ref().map(Deadline::resolve);
+        let terminate_after = spec.terminate_after.as_ref().map(Deadline::resolve);
+        if spec.spot {
             let variables = bid_spot::Variables {
                 input: bid_spot::PodRentInterruptableInput {
-                    name: Some(name),
-                    gpu_type_id: Some(gpu_type_id),
-                    gpu_count: Some(gpu_count),
-                    bid_per_gpu,
-                    template_id: template,
-                    container_disk_in_gb,
+                    name: Some(spec.name),
+                    gpu_type_id: Some(spec.gpu_type_id),
+                    gpu_count: Some(spec.gpu_count),
+                    bid_per_gpu: spec.bid_per_gpu,
+                    template_id: spec.template_id,
+                    container_disk_in_gb: spec.container_disk_in_gb,
                     start_ssh: Some(true),
                     volume_in_gb: Some(60),
                     cloud_type: Some(CloudTypeEnum::ALL),
@@ -276,6 +694,10 @@ impl RunpodClient {
                     min_memory_in_gb: Some(8),
                     min_vcpu_count: Some(2),
                     support_public_ip: Some(false),
+                    stop_after: stop_after.map(|t| t.to_string()),
+                    terminate_after: terminate_after.map(|t| t.to_string()),
+                    env: env_input(spec.env),
+                    container_registry_auth_id,
                     ..Default::default()
                 },
             };
@@ -283,16 +705,21 @@ impl RunpodClient {
             let response: bid_spot::ResponseData = self.request(&request_body).await?;
             let id = response
                 .pod_rent_interruptable
-                .ok_or(RunpodError::GraphQLError("Pod not created".to_string()))?
+                .ok_or(RunpodError::EmptyResponse("Pod not created"))?
                 .id;
             Ok(id)
         } else {
             let variables = spawn_pod_on_demand::Variables {
                 input: spawn_pod_on_demand::PodFindAndDeployOnDemandInput {
-                    name: Some(name),
-                    gpu_type_id: Some(gpu_type_id),
-                    gpu_count: Some(gpu_count),
-                    container_disk_in_gb,
+                    name: Some(spec.name),
+                    gpu_type_id: Some(spec.gpu_type_id),
+                    gpu_count: Some(spec.gpu_count),
+                    container_disk_in_gb: spec.container_disk_in_gb,
+                    stop_after,
+                    terminate_after,
+                    env: env_input(spec.env),
+                    container_registry_auth_id,
+                    savings_plan: spec.savings_plan.map(Into::into),
                     ..Default::default()
                 },
             };
@@ -301,12 +728,165 @@ impl RunpodClient {
             info!("response: {:?}", response);
             let id = response
                 .pod_find_and_deploy_on_demand
-                .ok_or(RunpodError::GraphQLError("Pod not created".to_string()))?
+                .ok_or(RunpodError::EmptyResponse("Pod not created"))?
                 .id;
             Ok(id)
         }
     }
 
+    /// Current spend rate, balance and spend limit for the account
+    pub async fn account_summary(&self) -> Result<AccountSummary> {
+        let variables = account_summary_query::Variables {};
+        let request_body = AccountSummaryQuery::build_query(variables);
+        let response: account_summary_query::ResponseData = self.request(&request_body).await?;
+        Ok(response.myself.into())
+    }
+
+    /// Teams the current user belongs to or owns
+    pub async fn list_teams(&self) -> Result<Vec<Team>> {
+        let variables = list_teams::Variables {};
+        let request_body = ListTeams::build_query(variables);
+        let response: list_teams::ResponseData = self.request(&request_body).await?;
+        let mut teams: Vec<Team> = Vec::new();
+        for team in all_teams(response.myself.teams, response.myself.owned_teams) {
+            let team = Team::from(team);
+            if !teams.iter().any(|t| t.id == team.id) {
+                teams.push(team);
+            }
+        }
+        Ok(teams)
+    }
+
+    /// Find a team by id or name
+    pub async fn find_team(&self, id_or_name: &str) -> Result<Team> {
+        self.list_teams()
+            .await?
+            .into_iter()
+            .find(|team| team.matches(id_or_name))
+            .ok_or_else(|| RunpodError::NotFound(format!("team {id_or_name}")))
+    }
+
+    /// Members and pending invites of a team, given by id or name
+    pub async fn team_members(&self, id_or_name: &str) -> Result<TeamRoster> {
+        let variables = team_members::Variables {};
+        let request_body = TeamMembers::build_query(variables);
+        let response: team_members::ResponseData = self.request(&request_body).await?;
+        all_teams(response.myself.teams, response.myself.owned_teams)
+            .map(TeamRoster::from)
+            .find(|roster| roster.matches(id_or_name))
+            .ok_or_else(|| RunpodError::NotFound(format!("team {id_or_name}")))
+    }
+
+    /// Machines this account hosts on Runpod
+    pub async fn list_machines(&self) -> Result<Vec<HostMachine>> {
+        let variables = host_machines::Variables {};
+        let request_body = HostMachines::build_query(variables);
+        let response: host_machines::ResponseData = self.request(&request_body).await?;
+        Ok(response
+            .myself
+            .machines
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Rental summary of each machine this account hosts
+    pub async fn machines_summary(&self) -> Result<Vec<MachineSummary>> {
+        let variables = machines_summary::Variables {};
+        let request_body = MachinesSummary::build_query(variables);
+        let response: machines_summary::ResponseData = self.request(&request_body).await?;
+        Ok(response
+            .myself
+            .machines_summary
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Daily earnings of hosted machines, either over `range` or over the API's default
+    /// recent period.
+    pub async fn machine_earnings(
+        &self,
+        range: Option<EarningsRange>,
+    ) -> Result<Vec<MachineEarning>> {
+        let earnings = match range {
+            Some(range) => {
+                let variables = machine_earnings_range::Variables {
+                    input: Some(range.into()),
+                };
+                let request_body = MachineEarningsRange::build_query(variables);
+                let response: machine_earnings_range::ResponseData =
+                    self.request(&request_body).await?;
+                response
+                    .myself
+                    .machine_earnings_custom_range
+                    .unwrap_or_default()
+                    .into_iter()
+                    .flatten()
+                    .map(Into::into)
+                    .collect()
+            }
+            None => {
+                let variables = machine_earnings::Variables {};
+                let request_body = MachineEarnings::build_query(variables);
+                let response: machine_earnings::ResponseData = self.request(&request_body).await?;
+                response
+                    .myself
+                    .machine_earnings
+                    .unwrap_or_default()
+                    .into_iter()
+                    .flatten()
+                    .map(Into::into)
+                    .collect()
+            }
+        };
+        Ok(earnings)
+    }
+
+    /// Upcoming maintenance windows across all hosted machines, earliest first and windows
+    /// without a start date last
+    pub async fn maintenance_schedule(&self) -> Result<Vec<MaintenanceWindow>> {
+        let mut windows: Vec<MaintenanceWindow> = self
+            .list_machines()
+            .await?
+            .into_iter()
+            .flat_map(|machine| machine.upcoming_maintenances)
+            .collect();
+        host::sort_by_start(&mut windows);
+        Ok(windows)
+    }
+
+    /// Savings plans bought on the account
+    pub async fn list_savings_plans(&self) -> Result<Vec<SavingsPlan>> {
+        let variables = list_savings_plans::Variables {};
+        let request_body = ListSavingsPlans::build_query(variables);
+        let response: list_savings_plans::ResponseData = self.request(&request_body).await?;
+        Ok(response
+            .myself
+            .savings_plans
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Billing history at the given granularity, plus the daily charge breakdown
+    pub async fn billing_report(&self, granularity: BillingGranularity) -> Result<BillingReport> {
+        let variables = billing_query::Variables {
+            input: billing_query::UserBillingInput {
+                granularity: Some(granularity.into()),
+            },
+        };
+        let request_body = BillingQuery::build_query(variables);
+        let response: billing_query::ResponseData = self.request(&request_body).await?;
+        Ok(response.myself.into())
+    }
+
     /// Get all templates for the current user
     pub async fn get_templates(&self) -> Result<Vec<Template>> {
         let variables = get_templates::Variables {};
@@ -315,7 +895,7 @@ impl RunpodClient {
         let templates = response
             .myself
             .pod_templates
-            .ok_or(RunpodError::GraphQLError("No templates found".to_string()))?
+            .ok_or(RunpodError::EmptyResponse("No templates found"))?
             .into_iter()
             .flatten()
             .map(Into::into)
@@ -331,15 +911,45 @@ impl RunpodClient {
     //     Ok(response.myself.template.into())
     // }
 
-    //// Save or update a template
-    // pub async fn save_template(&self, input: PodTemplateInput) -> Result<Template> {
-    //     let variables = save_template::Variables {
-    //         input: save_template::PodTemplateInput {},
-    //     };
-    //     let request_body = SaveTemplate::build_query(variables);
-    //     let response: save_template::ResponseData = self.request(&request_body).await?;
-    //     Ok(response.save_template.into())
-    // }
+    /// Save or update a template
+    pub async fn save_template(&self, spec: SaveTemplateSpec) -> Result<Template> {
+        let container_registry_auth_id = self
+            .resolve_registry_auth_option(spec.container_registry_auth.as_ref())
+            .await?;
+        let variables = save_template::Variables {
+            input: save_template::SaveTemplateInput {
+                id: spec.id,
+                name: Some(spec.name),
+                image_name: Some(spec.image_name),
+                container_disk_in_gb: Some(spec.container_disk_in_gb),
+                volume_in_gb: spec.volume_in_gb,
+                volume_mount_path: spec.volume_mount_path,
+                ports: spec.ports,
+                env: env_input(spec.env),
+                docker_args: spec.docker_args,
+                container_registry_auth_id,
+                ..Default::default()
+            },
+        };
+        let request_body = SaveTemplate::build_query(variables);
+        let response: save_template::ResponseData = self.request(&request_body).await?;
+        response
+            .save_template
+            .map(Into::into)
+            .ok_or(RunpodError::EmptyResponse("Template not saved"))
+    }
+
+    /// Introspect the live API and render its schema as SDL, for comparing with the
+    /// vendored `gql/schema.graphql`, see [`crate::schema`]
+    pub async fn fetch_schema(&self) -> Result<String> {
+        let request_body = QueryBody {
+            variables: (),
+            query: schema::INTROSPECTION_QUERY,
+            operation_name: "IntrospectionQuery",
+        };
+        let response: serde_json::Value = self.request(&request_body).await?;
+        schema::sdl_from_introspection(&response)
+    }
 
     // /// Remove a template by ID
     // pub async fn remove_template(&self, id: String) -> Result<()> {
@@ -352,6 +962,28 @@ impl RunpodClient {
     // }
 }
 
+/// Teams from `User.teams` followed by `User.ownedTeams`; a team may appear in both.
+fn all_teams<T>(
+    teams: Option<Vec<Option<T>>>,
+    owned: Option<Vec<Option<T>>>,
+) -> impl Iterator<Item = T> {
+    teams
+        .unwrap_or_default()
+        .into_iter()
+        .chain(owned.unwrap_or_default())
+        .flatten()
+}
+
+fn env_input<T: From<EnvironmentVariable>>(
+    env: Vec<EnvironmentVariable>,
+) -> Option<Vec<Option<T>>> {
+    if env.is_empty() {
+        None
+    } else {
+        Some(env.into_iter().map(|e| Some(e.into())).collect())
+    }
+}
+
 fn min_option<T: PartialOrd>(a: Option<T>, b: Option<T>) -> Option<T> {
     match (a, b) {
         (Some(x), Some(y)) => Some(if x <= y { x } else { y }),
@@ -471,6 +1103,125 @@ mod tests {
         RunpodClient::from_config().expect("no runpod login")
     }
 
+    /// Answers every request with the same body and remembers what was sent.
+    #[derive(Default)]
+    struct FakeTransport {
+        body: &'static str,
+        requests: std::sync::Mutex<Vec<TransportRequest>>,
+    }
+
+    impl Transport for Arc<FakeTransport> {
+        fn send(
+            &self,
+            request: TransportRequest,
+        ) -> futures::future::BoxFuture<'_, Result<crate::transport::TransportResponse>> {
+            self.requests.lock().unwrap().push(request);
+            let response = crate::transport::TransportResponse::ok(self.body);
+            Box::pin(async move { Ok(response) })
+        }
+    }
+
+    fn fake_client(body: &'static str) -> (RunpodClient, Arc<FakeTransport>) {
+        let transport = Arc::new(FakeTransport {
+            body,
+            ..Default::default()
+        });
+        let client = RunpodClient::new(Config::new("test-api-key", "http://localhost/graphql"))
+            .with_transport(transport.clone());
+        (client, transport)
+    }
+
+    #[tokio::test]
+    async fn sends_requests_through_transport() {
+        let (client, transport) = fake_client(r#"{"data":{"secretDelete":null}}"#);
+        client.delete_secret("s1").await.unwrap();
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].operation, "DeleteSecret");
+        assert_eq!(requests[0].headers[AUTHORIZATION], "Bearer test-api-key");
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body["variables"]["id"], "s1");
+    }
+
+    #[tokio::test]
+    async fn reports_request_metrics() {
+        let (client, _) = fake_client(r#"{"errors":[{"message":"boom"},{"message":"again"}]}"#);
+        let recorded = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = recorded.clone();
+        let client =
+            client.with_metrics(move |m: &RequestMetrics| sink.lock().unwrap().push(m.clone()));
+
+        assert!(client.delete_secret("s1").await.is_err());
+        let recorded = recorded.lock().unwrap();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].operation, "DeleteSecret");
+        assert_eq!(recorded[0].http_status, Some(200));
+        assert_eq!(recorded[0].graphql_errors, 2);
+        assert!(!recorded[0].success);
+    }
+
+    #[tokio::test]
+    async fn pod_lifecycle_against_fake_api() {
+        use crate::testing::{FakeGpu, FakeRunpod};
+
+        let fake = FakeRunpod::start().await;
+        fake.add_gpu(FakeGpu::new("RTX 4090", 0.69, 2));
+        let client = fake.client();
+        let spec = |count| {
+            SpawnPodSpec::builder()
+                .name("train")
+                .gpu_type_id("RTX 4090")
+                .gpu_count(count)
+                .template_id("tpl")
+                .build()
+        };
+
+        let id = client.spawn_pod(spec(2)).await.unwrap();
+        assert_eq!(fake.available("RTX 4090"), Some(0));
+        let pods = client.list_pods().await.unwrap();
+        assert_eq!(pods.len(), 1);
+        assert_eq!(pods[0].desired_status, PodStatus::Created);
+        assert_eq!(pods[0].adjusted_cost_per_hr, 1.38);
+        let pod = client.get_pod(&id).await.unwrap().unwrap();
+        assert_eq!(pod.desired_status, PodStatus::Running);
+
+        // The fake's error messages are assumed wording, so these only check that the
+        // client classifies what the fake sends, not what the real API sends.
+        assert!(matches!(
+            client.spawn_pod(spec(1)).await,
+            Err(RunpodError::InsufficientCapacity(_))
+        ));
+
+        client.stop_pod(&id).await.unwrap();
+        assert_eq!(fake.available("RTX 4090"), Some(2));
+        client.terminate_pod(&id).await.unwrap();
+        assert!(client.get_pod(&id).await.unwrap().is_none());
+        assert!(matches!(
+            client.stop_pod(&id).await,
+            Err(RunpodError::PodNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn fake_api_rate_limits_and_lists_gpus() {
+        use crate::testing::{FakeGpu, FakeRunpod};
+
+        let fake = FakeRunpod::start().await;
+        fake.add_gpu(FakeGpu::new("RTX 4090", 0.69, 2));
+        fake.add_gpu(FakeGpu::new("A100", 1.64, 0));
+        let client = fake.client();
+
+        fake.rate_limit_next(1);
+        assert!(matches!(
+            client.list_gpus(Some(true)).await,
+            Err(RunpodError::RateLimited)
+        ));
+        let gpus = client.list_gpus(None).await.unwrap();
+        assert_eq!(gpus.len(), 2);
+        assert_eq!(fake.operations(), vec!["GpuTypes"; 3]);
+    }
+
     #[ignore]
     #[test_log::test(tokio::test)]
     async fn test_list_pods() {
@@ -1,5 +1,7 @@
 use crate::error::Result;
+use crate::RunpodError;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 // runpodctl uses this config style:
 // ❯ cat /Users/grw/.runpod/config.toml
 // ───────┬──────────────────────────────
@@ -10,14 +12,44 @@ use serde::{Deserialize, Serialize};
 // ───────┴──────────────────────────────
 //
 // We attempt to load the config from the file and then use it to create a client.
+//
+// Runpod API keys belong to a single account, so a team is used through a key created in
+// that team, listed by team name:
+//
+//    [teams]
+//    research = "TEAM_APIKEY"
 
 #[derive(Debug, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct Config {
     pub apikey: String,
     pub apiurl: String,
+    /// Log full, unredacted request and response bodies at debug level.
+    #[serde(default)]
+    pub log_bodies: bool,
+    /// API keys for team accounts, by team name. See [`Config::for_team`].
+    #[serde(default)]
+    pub teams: BTreeMap<String, String>,
 }
 
 impl Config {
+    pub fn new(apikey: impl Into<String>, apiurl: impl Into<String>) -> Self {
+        Self {
+            apikey: apikey.into(),
+            apiurl: apiurl.into(),
+            log_bodies: false,
+            teams: BTreeMap::new(),
+        }
+    }
+
+    /// Use the API key configured for `team` in place of the personal one.
+    pub fn for_team(mut self, team: &str) -> Result<Self> {
+        self.apikey = self.teams.get(team).cloned().ok_or_else(|| {
+            RunpodError::NotFound(format!("API key for team {team} in the [teams] config"))
+        })?;
+        Ok(self)
+    }
+
     pub fn try_from_env() -> Result<Self> {
         let config_path = dirs::home_dir().unwrap().join(".runpod/config.toml");
         let config = std::fs::read_to_string(config_path)?;
@@ -25,3 +57,27 @@ impl Config {
         Ok(config)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selects_team_api_key() {
+        let config: Config = toml::from_str(
+            r#"
+            apikey = "personal"
+            apiurl = "https://api.runpod.io/graphql"
+
+            [teams]
+            research = "team-key"
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(
+            Config::new("personal", "").for_team("research"),
+            Err(RunpodError::NotFound(_))
+        ));
+        assert_eq!(config.for_team("research").unwrap().apikey, "team-key");
+    }
+}
@@ -0,0 +1,232 @@
+//! Timestamps returned by the API.
+//!
+//! Without the `chrono` feature every GraphQL `DateTime` is kept as the raw string the
+//! API sent. With it they are parsed into [`Timestamp`], a thin wrapper around
+//! `chrono::DateTime<Utc>`.
+
+use crate::error::Result;
+use std::time::Duration;
+
+#[cfg(not(feature = "chrono"))]
+pub type DateTime = String;
+
+#[cfg(feature = "chrono")]
+pub type DateTime = Timestamp;
+
+/// Parse a user-supplied time (RFC 3339 or a plain `YYYY-MM-DD` date) into a [`DateTime`].
+///
+/// Without the `chrono` feature times must be in UTC, with or without the trailing `Z`.
+#[cfg(not(feature = "chrono"))]
+pub fn parse_datetime(s: &str) -> Result<DateTime> {
+    let s = s.trim();
+    let parsed = if s.len() == "YYYY-MM-DD".len() {
+        humantime::parse_rfc3339_weak(&format!("{s} 00:00:00"))
+    } else {
+        humantime::parse_rfc3339_weak(s)
+    };
+    parsed
+        .map(|t| humantime::format_rfc3339(t).to_string())
+        .map_err(|_| crate::RunpodError::InvalidInput(format!("invalid timestamp: {s}")))
+}
+
+/// Parse a user-supplied time (RFC 3339 or a plain `YYYY-MM-DD` date) into a [`DateTime`].
+#[cfg(feature = "chrono")]
+pub fn parse_datetime(s: &str) -> Result<DateTime> {
+    s.parse()
+}
+
+/// The `YYYY-MM-DD` day of a time, in UTC.
+#[cfg(not(feature = "chrono"))]
+pub fn date_of(at: &DateTime) -> String {
+    at.get(..10).unwrap_or(at).to_string()
+}
+
+/// The `YYYY-MM-DD` day of a time, in UTC.
+#[cfg(feature = "chrono")]
+pub fn date_of(at: &DateTime) -> String {
+    at.format("%Y-%m-%d").to_string()
+}
+
+/// The time `after` from now.
+#[cfg(not(feature = "chrono"))]
+pub fn from_now(after: Duration) -> DateTime {
+    let now = std::time::SystemTime::now();
+    humantime::format_rfc3339_seconds(now.checked_add(after).unwrap_or(now)).to_string()
+}
+
+/// The time `after` from now.
+#[cfg(feature = "chrono")]
+pub fn from_now(after: Duration) -> DateTime {
+    let now = chrono::Utc::now();
+    Timestamp(
+        chrono::TimeDelta::from_std(after)
+            .ok()
+            .and_then(|after| now.checked_add_signed(after))
+            .unwrap_or(chrono::DateTime::<chrono::Utc>::MAX_UTC),
+    )
+}
+
+#[cfg(feature = "chrono")]
+pub use self::timestamp::Timestamp;
+
+#[cfg(feature = "chrono")]
+mod timestamp {
+    use crate::RunpodError;
+    use chrono::{NaiveDate, NaiveDateTime, TimeZone, Utc};
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    /// A point in time reported by the API, always in UTC.
+    ///
+    /// Derefs to `chrono::DateTime<Utc>`. Deserialization accepts RFC 3339, the
+    /// `2024-01-02 03:04:05.678 +0000 UTC` form some endpoints return, naive date-times
+    /// (taken as UTC), plain dates and unix timestamps in seconds or milliseconds.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct Timestamp(pub chrono::DateTime<Utc>);
+
+    impl Timestamp {
+        pub fn now() -> Self {
+            Self(Utc::now())
+        }
+
+        /// Time elapsed since this timestamp (negative if it is in the future).
+        pub fn elapsed(&self) -> chrono::TimeDelta {
+            Utc::now() - self.0
+        }
+
+        fn from_epoch(n: i64) -> Option<Self> {
+            // Anything past the year 5138 in seconds is assumed to be milliseconds.
+            if n.abs() >= 100_000_000_000 {
+                Utc.timestamp_millis_opt(n).single().map(Self)
+            } else {
+                Utc.timestamp_opt(n, 0).single().map(Self)
+            }
+        }
+
+        fn parse(s: &str) -> Option<Self> {
+            let s = s.trim();
+            if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(s) {
+                return Some(Self(dt.with_timezone(&Utc)));
+            }
+            if let Ok((dt, _)) = chrono::DateTime::parse_and_remainder(s, "%Y-%m-%d %H:%M:%S%.f %z")
+            {
+                return Some(Self(dt.with_timezone(&Utc)));
+            }
+            for fmt in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"] {
+                if let Ok(naive) = NaiveDateTime::parse_from_str(s, fmt) {
+                    return Some(Self(naive.and_utc()));
+                }
+            }
+            if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+                return Some(Self(date.and_hms_opt(0, 0, 0)?.and_utc()));
+            }
+            s.parse().ok().and_then(Self::from_epoch)
+        }
+    }
+
+    impl std::ops::Deref for Timestamp {
+        type Target = chrono::DateTime<Utc>;
+
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+
+    impl From<chrono::DateTime<Utc>> for Timestamp {
+        fn from(dt: chrono::DateTime<Utc>) -> Self {
+            Self(dt)
+        }
+    }
+
+    impl From<Timestamp> for chrono::DateTime<Utc> {
+        fn from(ts: Timestamp) -> Self {
+            ts.0
+        }
+    }
+
+    impl std::str::FromStr for Timestamp {
+        type Err = RunpodError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Self::parse(s)
+                .ok_or_else(|| RunpodError::InvalidInput(format!("invalid timestamp: {s}")))
+        }
+    }
+
+    impl std::fmt::Display for Timestamp {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(
+                f,
+                "{}",
+                self.0.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true)
+            )
+        }
+    }
+
+    impl Serialize for Timestamp {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(self)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Timestamp {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct Visitor;
+
+            impl de::Visitor<'_> for Visitor {
+                type Value = Timestamp;
+
+                fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    f.write_str("a timestamp string or unix time")
+                }
+
+                fn visit_str<E: de::Error>(self, v: &str) -> Result<Timestamp, E> {
+                    Timestamp::parse(v)
+                        .ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))
+                }
+
+                fn visit_i64<E: de::Error>(self, v: i64) -> Result<Timestamp, E> {
+                    Timestamp::from_epoch(v)
+                        .ok_or_else(|| E::invalid_value(de::Unexpected::Signed(v), &self))
+                }
+
+                fn visit_u64<E: de::Error>(self, v: u64) -> Result<Timestamp, E> {
+                    self.visit_i64(v as i64)
+                }
+
+                fn visit_f64<E: de::Error>(self, v: f64) -> Result<Timestamp, E> {
+                    self.visit_i64(v as i64)
+                }
+            }
+
+            deserializer.deserialize_any(Visitor)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn parses_api_formats() {
+            let expected = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+            for input in [
+                "\"2024-01-02T03:04:05Z\"",
+                "\"2024-01-02T03:04:05.000+00:00\"",
+                "\"2024-01-02 03:04:05 +0000 UTC\"",
+                "\"2024-01-02 03:04:05\"",
+                "1704164645",
+                "1704164645000",
+            ] {
+                let ts: Timestamp = serde_json::from_str(input).unwrap();
+                assert_eq!(ts.0, expected, "{input}");
+            }
+            let date: Timestamp = "2024-01-02".parse().unwrap();
+            assert_eq!(date.0, Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap());
+        }
+
+        #[test]
+        fn rejects_garbage() {
+            assert!(serde_json::from_str::<Timestamp>("\"yesterday\"").is_err());
+        }
+    }
+}
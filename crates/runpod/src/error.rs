@@ -1,3 +1,6 @@
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fmt;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -26,8 +29,39 @@ pub enum RunpodError {
     #[error("URL Parsing Error: {0}")]
     UrlParseError(#[from] url::ParseError),
 
-    #[error("Parse Error: {0}")]
-    GraphQLError(String),
+    /// The response was cut short and the rest can't be requested.
+    #[error("Incomplete response: {0}")]
+    IncompleteResponse(String),
+
+    /// The request succeeded but the expected object wasn't in the response.
+    #[error("Empty response: {0}")]
+    EmptyResponse(&'static str),
+
+    /// The response carried GraphQL errors that don't match a more specific variant.
+    #[error("GraphQL errors in {}: {0}", .0.operation)]
+    GraphQLErrors(GraphQLErrors),
+
+    /// No machine could satisfy the request, e.g. no GPUs of the requested type are free.
+    #[error("Insufficient capacity: {0}")]
+    InsufficientCapacity(GraphQLErrors),
+
+    #[error("Insufficient balance: {0}")]
+    InsufficientBalance(GraphQLErrors),
+
+    #[error("Invalid template: {0}")]
+    InvalidTemplate(GraphQLErrors),
+
+    #[error("Pod not found: {0}")]
+    PodNotFound(GraphQLErrors),
+
+    #[error("Budget exceeded: {0}")]
+    BudgetExceeded(String),
+
+    #[error("Notification failed: {0}")]
+    NotificationFailed(String),
+
+    #[error("Schema error: {0}")]
+    SchemaError(String),
 
     #[error("Toml Error: {0}")]
     TomlError(#[from] toml::de::Error),
@@ -36,10 +70,210 @@ pub enum RunpodError {
     IOError(#[from] std::io::Error),
 }
 
-impl From<graphql_client::Error> for RunpodError {
-    fn from(err: graphql_client::Error) -> Self {
-        RunpodError::GraphQLError(err.to_string())
+/// Every entry of a response's `errors` list, with whatever `data` came back alongside.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphQLErrors {
+    /// The GraphQL operation that failed, e.g. `SpawnPodOnDemand`.
+    pub operation: &'static str,
+    /// Messages, paths, locations and extensions as sent by the API.
+    pub errors: Vec<graphql_client::Error>,
+    /// Partial result returned with the errors. Never kept for requests carrying secrets.
+    pub data: Option<Value>,
+}
+
+/// Which [`RunpodError`] variant a set of errors maps to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ErrorKind {
+    InsufficientCapacity,
+    InsufficientBalance,
+    InvalidTemplate,
+    PodNotFound,
+}
+
+impl ErrorKind {
+    /// Best effort: the API documents neither error codes nor message wording, so this
+    /// matches substrings of messages seen in the wild and otherwise guessed. Anything it
+    /// doesn't recognise stays a plain [`RunpodError::GraphQLErrors`], so callers that need
+    /// certainty should inspect the messages themselves.
+    fn of(error: &graphql_client::Error) -> Option<Self> {
+        let code = error
+            .extensions
+            .as_ref()
+            .and_then(|e| e.get("code"))
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let message = error.message.to_ascii_lowercase();
+        let has = |needles: &[&str]| {
+            needles
+                .iter()
+                .any(|n| message.contains(n) || code.contains(n))
+        };
+
+        if has(&[
+            "insufficient_capacity",
+            "no longer any instances available",
+            "no instances currently available",
+            "does not have the resources",
+            "not enough free gpus",
+            "no gpus available",
+        ]) {
+            Some(ErrorKind::InsufficientCapacity)
+        } else if has(&[
+            "insufficient_balance",
+            "insufficient balance",
+            "insufficient funds",
+            "balance is too low",
+            "not enough balance",
+            "not enough credit",
+        ]) {
+            Some(ErrorKind::InsufficientBalance)
+        } else if message.contains("template")
+            && has(&["invalid", "not found", "does not exist", "not allowed"])
+        {
+            Some(ErrorKind::InvalidTemplate)
+        } else if has(&["pod not found", "could not find pod", "pod does not exist"]) {
+            Some(ErrorKind::PodNotFound)
+        } else {
+            None
+        }
+    }
+}
+
+impl GraphQLErrors {
+    /// The message of every error, in order.
+    pub fn messages(&self) -> impl Iterator<Item = &str> {
+        self.errors.iter().map(|e| e.message.as_str())
+    }
+
+    /// Deserialize the partial `data`, e.g. into the operation's `ResponseData`.
+    /// `None` if there was none or it doesn't have that shape.
+    pub fn partial_data<T: DeserializeOwned>(&self) -> Option<T> {
+        serde_json::from_value(self.data.clone()?).ok()
+    }
+}
+
+impl fmt::Display for GraphQLErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, error) in self.errors.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            if let Some(path) = &error.path {
+                let path: Vec<_> = path.iter().map(ToString::to_string).collect();
+                write!(f, "{}: ", path.join("."))?;
+            }
+            write!(f, "{}", error.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<GraphQLErrors> for RunpodError {
+    fn from(errors: GraphQLErrors) -> Self {
+        match errors.errors.iter().find_map(ErrorKind::of) {
+            Some(ErrorKind::InsufficientCapacity) => RunpodError::InsufficientCapacity(errors),
+            Some(ErrorKind::InsufficientBalance) => RunpodError::InsufficientBalance(errors),
+            Some(ErrorKind::InvalidTemplate) => RunpodError::InvalidTemplate(errors),
+            Some(ErrorKind::PodNotFound) => RunpodError::PodNotFound(errors),
+            None => RunpodError::GraphQLErrors(errors),
+        }
     }
 }
 
 pub type Result<T> = std::result::Result<T, RunpodError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn errors(messages: &[&str]) -> GraphQLErrors {
+        GraphQLErrors {
+            operation: "SpawnPodOnDemand",
+            errors: serde_json::from_value(json!(messages
+                .iter()
+                .map(|m| json!({"message": m, "path": ["podFindAndDeployOnDemand"]}))
+                .collect::<Vec<_>>()))
+            .unwrap(),
+            data: Some(json!({"podFindAndDeployOnDemand": null})),
+        }
+    }
+
+    /// A whole response body, as [`RunpodClient`](crate::RunpodClient) would receive it.
+    fn response_errors(body: serde_json::Value) -> GraphQLErrors {
+        let response: graphql_client::Response<Value> = serde_json::from_value(body).unwrap();
+        GraphQLErrors {
+            operation: "SpawnPodOnDemand",
+            errors: response.errors.unwrap(),
+            data: response.data,
+        }
+    }
+
+    #[test]
+    fn classifies_reported_capacity_error() {
+        // Wording as reported by users of the official SDKs and runpodctl; the body around it
+        // is the standard GraphQL error shape, not a capture from this crate.
+        let reported = response_errors(json!({
+            "errors": [{
+                "message": "There are no longer any instances available with the requested specifications. Please refresh and try again.",
+                "locations": [{"line": 2, "column": 3}],
+                "path": ["podFindAndDeployOnDemand"],
+                "extensions": {"code": "INTERNAL_SERVER_ERROR"}
+            }],
+            "data": {"podFindAndDeployOnDemand": null}
+        }));
+        assert!(matches!(
+            RunpodError::from(reported),
+            RunpodError::InsufficientCapacity(_)
+        ));
+
+        let unrecognised = response_errors(json!({
+            "errors": [{
+                "message": "Something went wrong. Please try again later or contact support.",
+                "path": ["podFindAndDeployOnDemand"],
+                "extensions": {"code": "INTERNAL_SERVER_ERROR"}
+            }],
+            "data": null
+        }));
+        assert!(matches!(
+            RunpodError::from(unrecognised),
+            RunpodError::GraphQLErrors(_)
+        ));
+    }
+
+    /// Wording below is assumed, not captured from the API.
+    #[test]
+    fn classifies_known_errors() {
+        let capacity = errors(&[
+            "There are no longer any instances available with the requested specifications.",
+        ]);
+        assert!(matches!(
+            RunpodError::from(capacity),
+            RunpodError::InsufficientCapacity(_)
+        ));
+        assert!(matches!(
+            RunpodError::from(errors(&["Insufficient balance to deploy"])),
+            RunpodError::InsufficientBalance(_)
+        ));
+        assert!(matches!(
+            RunpodError::from(errors(&["Template not found"])),
+            RunpodError::InvalidTemplate(_)
+        ));
+        assert!(matches!(
+            RunpodError::from(errors(&["Pod not found"])),
+            RunpodError::PodNotFound(_)
+        ));
+
+        let other = errors(&["Something odd", "Something else"]);
+        assert_eq!(
+            other.to_string(),
+            "podFindAndDeployOnDemand: Something odd; podFindAndDeployOnDemand: Something else"
+        );
+        assert_eq!(other.messages().count(), 2);
+        assert!(matches!(
+            RunpodError::from(other),
+            RunpodError::GraphQLErrors(_)
+        ));
+    }
+}
@@ -0,0 +1,349 @@
+//! Prometheus exporter for pod and account state.
+//!
+//! Every scrape of `/metrics` lists pods, the account summary and GPU prices, and renders
+//! them in the Prometheus text exposition format.
+
+use crate::error::Result;
+use crate::types::{AccountSummary, GpuOffer, Pod, PodStatus};
+use crate::RunpodClient;
+use std::fmt::Write as _;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{error, info, warn};
+
+const STATUSES: [PodStatus; 7] = [
+    PodStatus::Created,
+    PodStatus::Running,
+    PodStatus::Restarting,
+    PodStatus::Exited,
+    PodStatus::Paused,
+    PodStatus::Dead,
+    PodStatus::Terminated,
+];
+
+/// Everything one scrape reports.
+#[derive(Debug, Clone)]
+pub struct MetricsSnapshot {
+    pub pods: Vec<Pod>,
+    pub summary: AccountSummary,
+    pub gpus: Vec<GpuOffer>,
+}
+
+impl MetricsSnapshot {
+    /// Render the snapshot in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut out = MetricsWriter::default();
+
+        out.family(
+            "runpod_pod_cost_per_hour_dollars",
+            "Adjusted hourly cost of the pod.",
+        );
+        for pod in &self.pods {
+            out.sample(
+                "runpod_pod_cost_per_hour_dollars",
+                &pod_labels(pod),
+                Some(pod.adjusted_cost_per_hr),
+            );
+        }
+
+        out.family(
+            "runpod_pod_status",
+            "Desired status of the pod, 1 for the current status and 0 otherwise.",
+        );
+        for pod in &self.pods {
+            for status in STATUSES {
+                let status_label = status.to_string().to_ascii_lowercase();
+                let mut labels = pod_labels(pod);
+                labels.push(("status", &status_label));
+                let value = if pod.desired_status == status {
+                    1.0
+                } else {
+                    0.0
+                };
+                out.sample("runpod_pod_status", &labels, Some(value));
+            }
+        }
+
+        out.family(
+            "runpod_pod_gpu_count",
+            "Number of GPUs attached to the pod.",
+        );
+        for pod in &self.pods {
+            out.sample(
+                "runpod_pod_gpu_count",
+                &pod_labels(pod),
+                Some(pod.gpu_count as f64),
+            );
+        }
+
+        out.family(
+            "runpod_pod_gpu_utilization_percent",
+            "Average GPU utilization from the pod's latest telemetry.",
+        );
+        for pod in &self.pods {
+            out.sample(
+                "runpod_pod_gpu_utilization_percent",
+                &pod_labels(pod),
+                pod.gpu_utilization(),
+            );
+        }
+
+        out.family(
+            "runpod_pod_gpu_temperature_celsius",
+            "Average GPU temperature from the pod's latest telemetry.",
+        );
+        for pod in &self.pods {
+            let temperature = pod
+                .latest_telemetry
+                .as_ref()
+                .and_then(|t| t.average_gpu_metrics.as_ref())
+                .and_then(|g| g.temperature_celcius);
+            out.sample(
+                "runpod_pod_gpu_temperature_celsius",
+                &pod_labels(pod),
+                temperature,
+            );
+        }
+
+        out.family("runpod_account_balance_dollars", "Current account balance.");
+        out.sample(
+            "runpod_account_balance_dollars",
+            &[],
+            self.summary.client_balance,
+        );
+
+        out.family(
+            "runpod_account_spend_per_hour_dollars",
+            "Current spend per hour across all resources.",
+        );
+        out.sample(
+            "runpod_account_spend_per_hour_dollars",
+            &[],
+            self.summary.current_spend_per_hr,
+        );
+
+        out.family(
+            "runpod_gpu_price_per_hour_dollars",
+            "Market price per GPU per hour.",
+        );
+        for gpu in &self.gpus {
+            let prices = [
+                ("secure", "on_demand", gpu.secure_price),
+                ("community", "on_demand", gpu.community_price),
+                ("secure", "spot", gpu.secure_spot_price),
+                ("community", "spot", gpu.community_spot_price),
+            ];
+            for (cloud, kind, price) in prices {
+                out.sample(
+                    "runpod_gpu_price_per_hour_dollars",
+                    &[("gpu_type", &gpu.id), ("cloud", cloud), ("kind", kind)],
+                    price,
+                );
+            }
+        }
+
+        out.0
+    }
+}
+
+fn pod_labels(pod: &Pod) -> Vec<(&'static str, &str)> {
+    vec![("pod_id", &pod.id), ("pod_name", &pod.name)]
+}
+
+#[derive(Default)]
+struct MetricsWriter(String);
+
+impl MetricsWriter {
+    fn family(&mut self, name: &str, help: &str) {
+        let _ = writeln!(self.0, "# HELP {name} {help}");
+        let _ = writeln!(self.0, "# TYPE {name} gauge");
+    }
+
+    /// Write one sample. Missing values are left out rather than reported as zero.
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: Option<f64>) {
+        let Some(value) = value else { return };
+        self.0.push_str(name);
+        if !labels.is_empty() {
+            self.0.push('{');
+            for (i, (key, value)) in labels.iter().enumerate() {
+                if i > 0 {
+                    self.0.push(',');
+                }
+                let _ = write!(self.0, "{key}=\"{}\"", escape_label(value));
+            }
+            self.0.push('}');
+        }
+        let _ = writeln!(self.0, " {value}");
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// How long a connection may take to send its request before it is dropped.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Serves `/metrics` for a client.
+#[derive(Clone)]
+pub struct Exporter {
+    client: RunpodClient,
+}
+
+impl Exporter {
+    pub fn new(client: RunpodClient) -> Self {
+        Self { client }
+    }
+
+    /// Fetch the current state from the API.
+    pub async fn collect(&self) -> Result<MetricsSnapshot> {
+        let (pods, summary, gpus) = tokio::try_join!(
+            self.client.list_pods(),
+            self.client.account_summary(),
+            self.client.list_gpus(None),
+        )?;
+        Ok(MetricsSnapshot {
+            pods,
+            summary,
+            gpus,
+        })
+    }
+
+    /// Listen on `addr` and answer scrapes until the process is stopped.
+    pub async fn serve(&self, addr: SocketAddr) -> Result<()> {
+        let listener = TcpListener::bind(addr).await?;
+        info!(
+            "Serving metrics on http://{}/metrics",
+            listener.local_addr()?
+        );
+        self.serve_on(listener).await
+    }
+
+    /// Answer each connection on its own task, so a slow client can't hold up scrapes.
+    async fn serve_on(&self, listener: TcpListener) -> Result<()> {
+        loop {
+            let (stream, peer) = listener.accept().await?;
+            let exporter = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = exporter.handle(stream).await {
+                    warn!("Failed to answer scrape from {}: {}", peer, e);
+                }
+            });
+        }
+    }
+
+    async fn handle(&self, mut stream: TcpStream) -> Result<()> {
+        let mut buf = [0u8; 4096];
+        let n = tokio::time::timeout(READ_TIMEOUT, stream.read(&mut buf))
+            .await
+            .map_err(|_| std::io::Error::from(std::io::ErrorKind::TimedOut))??;
+        let request = String::from_utf8_lossy(&buf[..n]);
+        let path = request.split_whitespace().nth(1).unwrap_or("/");
+
+        let (status, body) = if path == "/metrics" || path.starts_with("/metrics?") {
+            match self.collect().await {
+                Ok(snapshot) => ("200 OK", snapshot.render()),
+                Err(e) => {
+                    error!("Failed to collect metrics: {}", e);
+                    ("500 Internal Server Error", format!("{e}\n"))
+                }
+            }
+        } else {
+            ("404 Not Found", "Not found, try /metrics\n".to_string())
+        };
+
+        let response = format!(
+            "HTTP/1.1 {status}\r\ncontent-type: text/plain; version=0.0.4\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{GpuTelemetry, PodTelemetry};
+
+    #[test]
+    fn renders_gauges() {
+        let pod = Pod {
+            name: "train \"a\"".into(),
+            desired_status: PodStatus::Running,
+            gpu_count: 2,
+            latest_telemetry: Some(PodTelemetry {
+                state: None,
+                time: None,
+                cpu_utilization: None,
+                memory_utilization: None,
+                average_gpu_metrics: Some(GpuTelemetry {
+                    id: None,
+                    percent_utilization: Some(87.5),
+                    temperature_celcius: None,
+                    memory_utilization: None,
+                    power_watts: None,
+                }),
+            }),
+            ..Pod::sample("p1", "p1")
+        };
+        let snapshot = MetricsSnapshot {
+            pods: vec![pod],
+            summary: AccountSummary {
+                current_spend_per_hr: Some(1.25),
+                client_balance: Some(42.0),
+                spend_limit: None,
+                under_balance: None,
+                credit_alert_threshold: None,
+                spend_details: None,
+            },
+            gpus: vec![],
+        };
+        let text = snapshot.render();
+        let labels = r#"pod_id="p1",pod_name="train \"a\"""#;
+        assert!(text.contains("# TYPE runpod_pod_cost_per_hour_dollars gauge\n"));
+        assert!(text.contains(&format!(
+            "runpod_pod_cost_per_hour_dollars{{{labels}}} 0.5\n"
+        )));
+        assert!(text.contains(&format!(
+            "runpod_pod_status{{{labels},status=\"running\"}} 1\n"
+        )));
+        assert!(text.contains(&format!(
+            "runpod_pod_status{{{labels},status=\"exited\"}} 0\n"
+        )));
+        assert!(text.contains(&format!("runpod_pod_gpu_count{{{labels}}} 2\n")));
+        assert!(text.contains(&format!(
+            "runpod_pod_gpu_utilization_percent{{{labels}}} 87.5\n"
+        )));
+        assert!(!text.contains("runpod_pod_gpu_temperature_celsius{"));
+        assert!(text.contains("runpod_account_balance_dollars 42\n"));
+        assert!(text.contains("runpod_account_spend_per_hour_dollars 1.25\n"));
+    }
+
+    #[tokio::test]
+    async fn silent_client_does_not_block_scrapes() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = RunpodClient::new(crate::config::Config::new(
+            "test-api-key",
+            "http://localhost/graphql",
+        ));
+        tokio::spawn(async move { Exporter::new(client).serve_on(listener).await });
+
+        let _silent = TcpStream::connect(addr).await.unwrap();
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(b"GET / HTTP/1.1\r\n\r\n").await.unwrap();
+        let mut response = String::new();
+        tokio::time::timeout(Duration::from_secs(5), stream.read_to_string(&mut response))
+            .await
+            .expect("scrape blocked by a silent connection")
+            .unwrap();
+        assert!(response.starts_with("HTTP/1.1 404 Not Found"));
+    }
+}
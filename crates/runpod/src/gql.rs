@@ -1,8 +1,10 @@
 use graphql_client::GraphQLQuery;
 
-pub type DateTime = String;
+pub use crate::datetime::DateTime;
 pub type Port = u16;
 pub type JSON = serde_json::Value;
+/// A calendar date, `YYYY-MM-DD`.
+pub type Date = String;
 
 #[derive(GraphQLQuery)]
 #[graphql(
@@ -76,6 +78,167 @@ pub struct BidSpot;
 )]
 pub struct GetTemplates;
 
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "gql/schema.graphql",
+    query_path = "gql/account_summary.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone"
+)]
+pub struct AccountSummaryQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "gql/schema.graphql",
+    query_path = "gql/billing.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone"
+)]
+pub struct BillingQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "gql/schema.graphql",
+    query_path = "gql/secrets.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone"
+)]
+pub struct ListSecrets;
+
+// Secret values travel in these variables, so they deliberately do not derive `Debug`.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "gql/schema.graphql",
+    query_path = "gql/secrets.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct CreateSecret;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "gql/schema.graphql",
+    query_path = "gql/secrets.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct UpdateSecret;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "gql/schema.graphql",
+    query_path = "gql/secrets.graphql",
+    response_derives = "Debug",
+    variables_derives = "Debug"
+)]
+pub struct DeleteSecret;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "gql/schema.graphql",
+    query_path = "gql/api_keys.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone"
+)]
+pub struct ListApiKeys;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "gql/schema.graphql",
+    query_path = "gql/audit_logs.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone, Default"
+)]
+pub struct AuditLogs;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "gql/schema.graphql",
+    query_path = "gql/host.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone"
+)]
+pub struct HostMachines;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "gql/schema.graphql",
+    query_path = "gql/host.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone"
+)]
+pub struct MachinesSummary;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "gql/schema.graphql",
+    query_path = "gql/host.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone"
+)]
+pub struct MachineEarnings;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "gql/schema.graphql",
+    query_path = "gql/host.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone"
+)]
+pub struct MachineEarningsRange;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "gql/schema.graphql",
+    query_path = "gql/registry_auth.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone"
+)]
+pub struct ListRegistryAuths;
+
+// The registry password travels in these variables, so they deliberately do not derive `Debug`.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "gql/schema.graphql",
+    query_path = "gql/registry_auth.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct SaveRegistryAuth;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "gql/schema.graphql",
+    query_path = "gql/registry_auth.graphql",
+    response_derives = "Debug",
+    variables_derives = "Debug"
+)]
+pub struct DeleteRegistryAuth;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "gql/schema.graphql",
+    query_path = "gql/savings_plans.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone"
+)]
+pub struct ListSavingsPlans;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "gql/schema.graphql",
+    query_path = "gql/teams.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone"
+)]
+pub struct ListTeams;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "gql/schema.graphql",
+    query_path = "gql/teams.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone"
+)]
+pub struct TeamMembers;
+
 // #[derive(GraphQLQuery)]
 // #[graphql(
 //     schema_path = "gql/schema.graphql",
@@ -85,14 +248,14 @@ pub struct GetTemplates;
 // )]
 // pub struct GetTemplate;
 
-// #[derive(GraphQLQuery)]
-// #[graphql(
-//     schema_path = "gql/schema.graphql",
-//     query_path = "gql/save_template.graphql",
-//     response_derives = "Debug, Clone",
-//     variables_derives = "Debug, Clone"
-// )]
-// pub struct SaveTemplate;
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "gql/schema.graphql",
+    query_path = "gql/save_template.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Default"
+)]
+pub struct SaveTemplate;
 
 // #[derive(GraphQLQuery)]
 // #[graphql(
@@ -0,0 +1,316 @@
+//! The hosting side of the API: machines the account provides to Runpod, what they earn
+//! and when they are scheduled for maintenance.
+
+use crate::datetime;
+use crate::gql::{
+    host_machines, machine_earnings, machine_earnings_range, machines_summary, Date, DateTime,
+};
+
+/// A machine hosted on Runpod by this account.
+#[cfg_attr(
+    feature = "tabled",
+    derive(tabled::Tabled),
+    tabled(display(Option, "crate::types::display_option", ""))
+)]
+#[derive(Debug, Clone)]
+pub struct HostMachine {
+    pub id: String,
+    pub name: Option<String>,
+    pub gpu_type_id: Option<String>,
+    pub gpu_total: Option<i64>,
+    pub gpu_reserved: Option<i64>,
+    pub location: Option<String>,
+    pub listed: Option<bool>,
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub verified: Option<bool>,
+    pub host_price_per_gpu: Option<f64>,
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub host_min_bid_per_gpu: Option<f64>,
+    /// Percentage of the last four weeks the machine was listed and reachable.
+    pub uptime_percent_four_week: Option<f64>,
+    pub maintenance_mode: Option<bool>,
+    pub total_earnings: Option<f64>,
+    pub gpu_utilization: Option<f64>,
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub cpu_utilization: Option<f64>,
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub memory_utilization: Option<f64>,
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub telemetry_time: Option<DateTime>,
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub uptime_in_seconds: Option<i64>,
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub upcoming_maintenances: Vec<MaintenanceWindow>,
+}
+
+impl HostMachine {
+    /// The earliest upcoming maintenance window, if any.
+    pub fn next_maintenance(&self) -> Option<&MaintenanceWindow> {
+        self.upcoming_maintenances
+            .iter()
+            .filter(|m| m.start.is_some())
+            .min_by(|a, b| a.start.cmp(&b.start))
+    }
+}
+
+impl From<host_machines::HostMachinesMyselfMachines> for HostMachine {
+    fn from(machine: host_machines::HostMachinesMyselfMachines) -> Self {
+        let telemetry = machine.latest_telemetry;
+        let upcoming_maintenances = machine
+            .upcoming_maintenances
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .map(|m| MaintenanceWindow {
+                id: m.id,
+                machine_id: m.machine_id,
+                machine_name: machine.name.clone(),
+                start: m.maintenance_start,
+                end: m.maintenance_end,
+                note: m.maintenance_note,
+            })
+            .collect();
+        Self {
+            id: machine.id.unwrap_or_default(),
+            name: machine.name,
+            gpu_type_id: machine.gpu_type_id,
+            gpu_total: machine.gpu_total,
+            gpu_reserved: machine.gpu_reserved,
+            location: machine.location,
+            listed: machine.listed,
+            verified: machine.verified,
+            host_price_per_gpu: machine.host_price_per_gpu,
+            host_min_bid_per_gpu: machine.host_min_bid_per_gpu,
+            uptime_percent_four_week: machine.uptime_percent_listed_four_week,
+            maintenance_mode: machine.maintenance_mode,
+            total_earnings: machine.machine_balance.and_then(|b| b.host_total_earnings),
+            gpu_utilization: telemetry
+                .as_ref()
+                .and_then(|t| t.average_gpu_metrics.as_ref())
+                .and_then(|g| g.percent_utilization),
+            cpu_utilization: telemetry.as_ref().and_then(|t| t.cpu_utilization),
+            memory_utilization: telemetry.as_ref().and_then(|t| t.memory_utilization),
+            telemetry_time: telemetry.and_then(|t| t.time),
+            uptime_in_seconds: machine.uptime.and_then(|u| u.seconds),
+            upcoming_maintenances,
+        }
+    }
+}
+
+/// Rental summary of one hosted machine: how much of it is rented and what it makes.
+#[cfg_attr(
+    feature = "tabled",
+    derive(tabled::Tabled),
+    tabled(display(Option, "crate::types::display_option", ""))
+)]
+#[derive(Debug, Clone)]
+pub struct MachineSummary {
+    pub id: Option<String>,
+    pub display_name: Option<String>,
+    pub gpu_type_id: Option<String>,
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub cpu_type_id: Option<String>,
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub machine_type: Option<String>,
+    pub gpu_total: Option<i64>,
+    pub gpu_rented: Option<i64>,
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub vcpu_total: Option<i64>,
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub cpu_rented: Option<i64>,
+    /// Number of GPUs listed for rent.
+    pub listed: Option<i64>,
+    pub on_demand_pods: Option<i64>,
+    pub spot_pods: Option<i64>,
+    pub pod_profit_per_hr: Option<f64>,
+    pub disk_profit_per_hr: Option<f64>,
+}
+
+impl From<machines_summary::MachinesSummaryMyselfMachinesSummary> for MachineSummary {
+    fn from(summary: machines_summary::MachinesSummaryMyselfMachinesSummary) -> Self {
+        Self {
+            id: summary.id,
+            display_name: summary.display_name,
+            gpu_type_id: summary.gpu_type_id,
+            cpu_type_id: summary.cpu_type_id,
+            machine_type: summary.machine_type,
+            gpu_total: summary.gpu_total,
+            gpu_rented: summary.gpu_rented,
+            vcpu_total: summary.vcpu_total,
+            cpu_rented: summary.cpu_rented,
+            listed: summary.listed,
+            on_demand_pods: summary.on_demand_pods,
+            spot_pods: summary.spot_pods,
+            pod_profit_per_hr: summary.pod_profit_per_hr,
+            disk_profit_per_hr: summary.disk_profit_per_hr,
+        }
+    }
+}
+
+/// A scheduled maintenance window for a hosted machine.
+#[cfg_attr(
+    feature = "tabled",
+    derive(tabled::Tabled),
+    tabled(display(Option, "crate::types::display_option", ""))
+)]
+#[derive(Debug, Clone)]
+pub struct MaintenanceWindow {
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub id: Option<String>,
+    pub machine_id: Option<String>,
+    pub machine_name: Option<String>,
+    pub start: Option<DateTime>,
+    pub end: Option<DateTime>,
+    pub note: Option<String>,
+}
+
+/// Sort windows earliest first, with unscheduled windows (no start) last.
+pub(crate) fn sort_by_start(windows: &mut [MaintenanceWindow]) {
+    windows.sort_by(|a, b| (a.start.is_none(), &a.start).cmp(&(b.start.is_none(), &b.start)));
+}
+
+/// Earnings of one machine for one day.
+#[cfg_attr(
+    feature = "tabled",
+    derive(tabled::Tabled),
+    tabled(display(Option, "crate::types::display_option", ""))
+)]
+#[derive(Debug, Clone)]
+pub struct MachineEarning {
+    pub date: Option<DateTime>,
+    pub machine_id: Option<String>,
+    pub name: Option<String>,
+    pub total: Option<f64>,
+    pub gpu: Option<f64>,
+    pub disk: Option<f64>,
+}
+
+impl From<machine_earnings::MachineEarningsMyselfMachineEarnings> for MachineEarning {
+    fn from(earning: machine_earnings::MachineEarningsMyselfMachineEarnings) -> Self {
+        Self {
+            date: earning.date,
+            machine_id: earning.machine_id,
+            name: earning.name,
+            total: earning.host_total_earnings,
+            gpu: earning.host_gpu_earnings,
+            disk: earning.host_disk_earnings,
+        }
+    }
+}
+
+impl From<machine_earnings_range::MachineEarningsRangeMyselfMachineEarningsCustomRange>
+    for MachineEarning
+{
+    fn from(
+        earning: machine_earnings_range::MachineEarningsRangeMyselfMachineEarningsCustomRange,
+    ) -> Self {
+        Self {
+            date: earning.date,
+            machine_id: earning.machine_id,
+            name: earning.name,
+            total: earning.host_total_earnings,
+            gpu: earning.host_gpu_earnings,
+            disk: earning.host_disk_earnings,
+        }
+    }
+}
+
+/// An inclusive range of days to report earnings for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EarningsRange {
+    /// First day, `YYYY-MM-DD`.
+    pub start: Date,
+    /// Last day, `YYYY-MM-DD`.
+    pub end: Date,
+}
+
+impl EarningsRange {
+    /// The days from `start` to `end`, both included, taken in UTC.
+    pub fn between(start: &DateTime, end: &DateTime) -> Self {
+        Self {
+            start: datetime::date_of(start),
+            end: datetime::date_of(end),
+        }
+    }
+}
+
+impl From<EarningsRange> for machine_earnings_range::EarningsCustomRangeInput {
+    fn from(range: EarningsRange) -> Self {
+        Self {
+            start_date: range.start,
+            end_date: range.end,
+        }
+    }
+}
+
+/// Total earnings per machine, in order of first appearance.
+pub fn total_by_machine(earnings: &[MachineEarning]) -> Vec<(String, f64)> {
+    let mut totals: Vec<(String, f64)> = Vec::new();
+    for earning in earnings {
+        let key = earning
+            .name
+            .clone()
+            .or_else(|| earning.machine_id.clone())
+            .unwrap_or_default();
+        let amount = earning.total.unwrap_or(0.0);
+        match totals.iter_mut().find(|(name, _)| *name == key) {
+            Some((_, total)) => *total += amount,
+            None => totals.push((key, amount)),
+        }
+    }
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn earning(name: &str, total: f64) -> MachineEarning {
+        MachineEarning {
+            date: None,
+            machine_id: None,
+            name: Some(name.into()),
+            total: Some(total),
+            gpu: None,
+            disk: None,
+        }
+    }
+
+    #[test]
+    fn totals_earnings_per_machine() {
+        let earnings = vec![earning("a", 1.5), earning("b", 2.0), earning("a", 0.5)];
+        assert_eq!(
+            total_by_machine(&earnings),
+            vec![("a".to_string(), 2.0), ("b".to_string(), 2.0)]
+        );
+    }
+
+    #[test]
+    fn unscheduled_maintenance_sorts_last() {
+        let window = |id: &str, start: Option<&str>| MaintenanceWindow {
+            id: Some(id.into()),
+            machine_id: None,
+            machine_name: None,
+            start: start.map(|s| datetime::parse_datetime(s).unwrap()),
+            end: None,
+            note: None,
+        };
+        let mut windows = vec![
+            window("unscheduled", None),
+            window("late", Some("2026-11-02T00:00:00Z")),
+            window("early", Some("2026-10-20T00:00:00Z")),
+        ];
+        sort_by_start(&mut windows);
+        let ids: Vec<_> = windows.iter().map(|w| w.id.as_deref().unwrap()).collect();
+        assert_eq!(ids, ["early", "late", "unscheduled"]);
+    }
+
+    #[test]
+    fn earnings_range_uses_utc_days() {
+        let start = datetime::parse_datetime("2026-09-01").unwrap();
+        let end = datetime::parse_datetime("2026-09-30T23:59:59Z").unwrap();
+        let range = EarningsRange::between(&start, &end);
+        assert_eq!(range.start, "2026-09-01");
+        assert_eq!(range.end, "2026-09-30");
+    }
+}
@@ -1,8 +1,32 @@
+pub mod audit;
+pub mod billing;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod budget;
+#[cfg(any(test, feature = "testing"))]
+pub mod cassette;
 pub mod client;
 pub mod config;
+pub mod datetime;
 pub mod error;
+#[cfg(feature = "daemons")]
+pub mod exporter;
 pub mod gql;
+pub mod host;
+pub mod metrics;
+#[cfg(feature = "daemons")]
+pub mod notify;
+#[cfg(feature = "daemons")]
+pub mod reaper;
+pub mod redact;
+pub mod savings;
+pub mod schema;
+pub mod teams;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod transport;
 pub mod types;
+pub mod watch;
 
 pub use client::RunpodClient;
 pub use error::{Result, RunpodError};
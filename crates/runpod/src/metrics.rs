@@ -0,0 +1,40 @@
+//! Hook for recording per-request metrics from [`RunpodClient`](crate::RunpodClient).
+//!
+//! Every GraphQL call already runs in a `graphql` tracing span carrying the operation name,
+//! latency, HTTP status and GraphQL error count. To also feed counters and histograms,
+//! implement [`MetricsHook`] and install it with
+//! [`RunpodClient::with_metrics`](crate::RunpodClient::with_metrics).
+
+use std::time::Duration;
+
+/// The outcome of one GraphQL call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestMetrics {
+    /// The GraphQL operation name, e.g. `GetPod` or `SpawnPodOnDemand`.
+    pub operation: &'static str,
+    /// Time from sending the request to having handled the response.
+    pub latency: Duration,
+    /// HTTP status of the response. `None` if no response was received.
+    pub http_status: Option<u16>,
+    /// Number of entries in the response's `errors` list.
+    pub graphql_errors: usize,
+    /// Whether the call returned data.
+    pub success: bool,
+}
+
+/// Receives [`RequestMetrics`] after every call, successful or not.
+///
+/// Called inline on the request path, so implementations should be cheap, e.g. bumping
+/// a counter and observing a histogram.
+pub trait MetricsHook: Send + Sync {
+    fn record(&self, metrics: &RequestMetrics);
+}
+
+impl<F> MetricsHook for F
+where
+    F: Fn(&RequestMetrics) + Send + Sync,
+{
+    fn record(&self, metrics: &RequestMetrics) {
+        self(metrics)
+    }
+}
@@ -0,0 +1,679 @@
+//! Notifications for pod lifecycle events and low balance, driven by rules from a TOML file.
+//!
+//! ```toml
+//! interval = "1m"
+//! timeout = "30s"
+//!
+//! [[rule]]
+//! name = "spot-preempted"
+//! trigger = "preempted"
+//! webhook = "https://hooks.example.com/runpod"
+//!
+//! [[rule]]
+//! trigger = "idle"
+//! pods = ["exp-*"]
+//! idle = "2h"
+//! command = "notify-send \"$RUNPOD_MESSAGE\""
+//!
+//! [[rule]]
+//! trigger = "low_balance"
+//! webhook = "https://hooks.example.com/runpod"
+//! ```
+//!
+//! Webhooks receive the [`Notification`] as a JSON `POST` body. Commands run through
+//! `sh -c` with `RUNPOD_TRIGGER`, `RUNPOD_MESSAGE`, `RUNPOD_POD_ID`, `RUNPOD_POD_NAME` and
+//! the full JSON in `RUNPOD_NOTIFICATION` set in their environment.
+//!
+//! A webhook that hasn't answered, or a command that hasn't exited, within `timeout` counts
+//! as a failed delivery; the command is killed. Failures are logged and the remaining
+//! notifications are still sent.
+
+use crate::error::Result;
+use crate::reaper::{Reaper, ReaperConfig};
+use crate::types::{AccountSummary, Pod, PodStatus};
+use crate::watch::{PodEvent, PodWatcher};
+use crate::{RunpodClient, RunpodError};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+use wildmatch::WildMatch;
+
+/// What a rule fires on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Trigger {
+    /// A running pod exited or died.
+    PodDied,
+    /// A spot pod was preempted.
+    Preempted,
+    /// A pod's GPUs have been idle for longer than the rule's `idle` duration.
+    Idle,
+    /// The balance fell below the rule's `below` amount, or the account's
+    /// `creditAlertThreshold` if the rule has none.
+    LowBalance,
+}
+
+impl std::fmt::Display for Trigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Trigger::PodDied => write!(f, "pod_died"),
+            Trigger::Preempted => write!(f, "preempted"),
+            Trigger::Idle => write!(f, "idle"),
+            Trigger::LowBalance => write!(f, "low_balance"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NotifyRule {
+    /// Name reported in notifications. Defaults to the trigger name.
+    pub name: Option<String>,
+    pub trigger: Trigger,
+    /// Pod name patterns (`*` and `?` wildcards) the rule applies to. Empty means all pods.
+    #[serde(default)]
+    pub pods: Vec<String>,
+    /// How long a pod must be idle before an `idle` rule fires.
+    #[serde(default, deserialize_with = "deserialize_duration")]
+    pub idle: Option<Duration>,
+    /// GPU utilization percentage at or below which a pod counts as idle.
+    #[serde(default)]
+    pub gpu_threshold: f64,
+    /// Balance threshold for `low_balance` rules.
+    pub below: Option<f64>,
+    /// URL to `POST` the notification to as JSON.
+    pub webhook: Option<String>,
+    /// Shell command to run for each notification.
+    pub command: Option<String>,
+}
+
+impl NotifyRule {
+    pub fn name(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| self.trigger.to_string())
+    }
+
+    fn matches(&self, pod: &Pod) -> bool {
+        self.pods.is_empty()
+            || self
+                .pods
+                .iter()
+                .any(|p| WildMatch::new(p).matches(&pod.name))
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.webhook.is_none() && self.command.is_none() {
+            return Err(RunpodError::InvalidInput(format!(
+                "rule {} needs a webhook or a command",
+                self.name()
+            )));
+        }
+        if self.trigger == Trigger::Idle && self.idle.is_none() {
+            return Err(RunpodError::InvalidInput(format!(
+                "idle rule {} needs an idle duration",
+                self.name()
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn default_interval() -> Duration {
+    Duration::from_secs(60)
+}
+
+fn deserialize_duration<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<Duration>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|s| humantime::parse_duration(&s).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+fn deserialize_interval<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Duration, D::Error> {
+    Ok(deserialize_duration(deserializer)?.unwrap_or_else(default_interval))
+}
+
+fn default_timeout() -> Duration {
+    Duration::from_secs(30)
+}
+
+fn deserialize_timeout<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Duration, D::Error> {
+    Ok(deserialize_duration(deserializer)?.unwrap_or_else(default_timeout))
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NotifyConfig {
+    /// Time between polls.
+    #[serde(
+        default = "default_interval",
+        deserialize_with = "deserialize_interval"
+    )]
+    pub interval: Duration,
+    /// How long one webhook call or command may take.
+    #[serde(default = "default_timeout", deserialize_with = "deserialize_timeout")]
+    pub timeout: Duration,
+    #[serde(default, rename = "rule")]
+    pub rules: Vec<NotifyRule>,
+}
+
+impl NotifyConfig {
+    pub fn from_toml(s: &str) -> Result<Self> {
+        let config: NotifyConfig = toml::from_str(s)?;
+        for rule in &config.rules {
+            rule.validate()?;
+        }
+        Ok(config)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_toml(&std::fs::read_to_string(path)?)
+    }
+}
+
+/// What is sent to webhooks and commands.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Notification {
+    pub rule: String,
+    pub trigger: Trigger,
+    pub message: String,
+    pub pod_id: Option<String>,
+    pub pod_name: Option<String>,
+    pub balance: Option<f64>,
+}
+
+impl Notification {
+    fn for_pod(rule: &NotifyRule, pod: &Pod, message: String) -> Self {
+        Self {
+            rule: rule.name(),
+            trigger: rule.trigger,
+            message,
+            pod_id: Some(pod.id.clone()),
+            pod_name: Some(pod.name.clone()),
+            balance: None,
+        }
+    }
+}
+
+/// Polls pods (and the balance, if any rule needs it) and sends notifications for
+/// matching rules.
+pub struct Notifier {
+    client: RunpodClient,
+    http: reqwest::Client,
+    config: NotifyConfig,
+    watcher: PodWatcher,
+    /// Idle tracking for each `idle` rule, indexed like `config.rules`.
+    idle: Vec<Option<Reaper>>,
+    /// (rule index, pod id) pairs already notified for the current idle period.
+    idle_notified: HashSet<(usize, String)>,
+    /// Rules whose low-balance alert has fired and not yet recovered.
+    low_balance_notified: HashSet<usize>,
+}
+
+impl Notifier {
+    pub fn new(client: RunpodClient, config: NotifyConfig) -> Self {
+        let idle = config
+            .rules
+            .iter()
+            .map(|rule| {
+                let threshold = rule.idle.filter(|_| rule.trigger == Trigger::Idle)?;
+                let reaper_config = ReaperConfig::builder()
+                    .idle_threshold(threshold)
+                    .gpu_utilization_threshold(rule.gpu_threshold)
+                    .allow(rule.pods.clone())
+                    .dry_run(true)
+                    .assume_idle_since_start(true)
+                    .build();
+                Some(Reaper::new(client.clone(), reaper_config))
+            })
+            .collect();
+        Self {
+            client,
+            http: reqwest::Client::builder()
+                .timeout(config.timeout)
+                .build()
+                .expect("Failed to create HTTP client"),
+            config,
+            watcher: PodWatcher::new(),
+            idle,
+            idle_notified: HashSet::new(),
+            low_balance_notified: HashSet::new(),
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.config.interval
+    }
+
+    /// Poll once and send any notifications. Failed deliveries are logged and do not
+    /// stop the others, and if the account summary can't be fetched only the balance
+    /// rules are skipped.
+    pub async fn run_once(&mut self) -> Result<Vec<Notification>> {
+        let pods = self.client.list_pods().await?;
+        let needs_balance = self
+            .config
+            .rules
+            .iter()
+            .any(|r| r.trigger == Trigger::LowBalance);
+        let summary = if needs_balance {
+            self.client
+                .account_summary()
+                .await
+                .inspect_err(|e| warn!("Failed to fetch account summary: {}", e))
+                .ok()
+        } else {
+            None
+        };
+
+        let notifications = self.evaluate(pods, summary.as_ref(), Instant::now());
+        for (index, notification) in &notifications {
+            let rule = &self.config.rules[*index];
+            info!("{}: {}", notification.rule, notification.message);
+            if let Err(e) = self.send(rule, notification).await {
+                error!("Failed to deliver notification for {}: {}", rule.name(), e);
+            }
+        }
+        Ok(notifications.into_iter().map(|(_, n)| n).collect())
+    }
+
+    /// Poll forever, sleeping for the configured interval between polls.
+    pub async fn run(&mut self) -> Result<()> {
+        loop {
+            if let Err(e) = self.run_once().await {
+                warn!("Notifier poll failed: {}", e);
+            }
+            tokio::time::sleep(self.config.interval).await;
+        }
+    }
+
+    fn evaluate(
+        &mut self,
+        pods: Vec<Pod>,
+        summary: Option<&AccountSummary>,
+        now: Instant,
+    ) -> Vec<(usize, Notification)> {
+        let mut out = Vec::new();
+
+        for (index, reaper) in self.idle.iter_mut().enumerate() {
+            let Some(reaper) = reaper else { continue };
+            let rule = &self.config.rules[index];
+            let idle: Vec<_> = reaper.select(&pods, now);
+            self.idle_notified
+                .retain(|(i, id)| *i != index || idle.iter().any(|o| o.pod_id == *id));
+            for outcome in idle {
+                if self.idle_notified.insert((index, outcome.pod_id.clone())) {
+                    out.push((
+                        index,
+                        Notification {
+                            rule: rule.name(),
+                            trigger: rule.trigger,
+                            message: format!(
+                                "Pod {} ({}) has been idle for {} at ${:.3}/hr",
+                                outcome.pod_name,
+                                outcome.pod_id,
+                                humantime::format_duration(outcome.idle_for),
+                                outcome.adjusted_cost_per_hr
+                            ),
+                            pod_id: Some(outcome.pod_id),
+                            pod_name: Some(outcome.pod_name),
+                            balance: None,
+                        },
+                    ));
+                }
+            }
+        }
+
+        for event in self.watcher.diff(pods) {
+            for (index, rule) in self.config.rules.iter().enumerate() {
+                if let Some(notification) = event_notification(rule, &event) {
+                    out.push((index, notification));
+                }
+            }
+        }
+
+        if let Some(summary) = summary {
+            let balance = summary.client_balance.unwrap_or(0.0);
+            for (index, rule) in self.config.rules.iter().enumerate() {
+                if rule.trigger != Trigger::LowBalance {
+                    continue;
+                }
+                let Some(threshold) = rule.below.or(summary.credit_alert_threshold) else {
+                    continue;
+                };
+                if balance >= threshold {
+                    self.low_balance_notified.remove(&index);
+                } else if self.low_balance_notified.insert(index) {
+                    out.push((
+                        index,
+                        Notification {
+                            rule: rule.name(),
+                            trigger: rule.trigger,
+                            message: format!("Balance ${balance:.2} is below ${threshold:.2}"),
+                            pod_id: None,
+                            pod_name: None,
+                            balance: Some(balance),
+                        },
+                    ));
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Deliver a notification through the rule's webhook and command. Both are tried even
+    /// if one fails; the first failure is returned as [`RunpodError::NotificationFailed`].
+    pub async fn send(&self, rule: &NotifyRule, notification: &Notification) -> Result<()> {
+        let mut result = Ok(());
+        if let Some(url) = &rule.webhook {
+            result = result.and(self.post_webhook(url, notification).await);
+        }
+        if let Some(command) = &rule.command {
+            result = result.and(self.run_command(command, notification).await);
+        }
+        result
+    }
+
+    async fn post_webhook(&self, url: &str, notification: &Notification) -> Result<()> {
+        let response = self
+            .http
+            .post(url)
+            .json(notification)
+            .send()
+            .await
+            .map_err(|e| RunpodError::NotificationFailed(format!("webhook {url}: {e}")))?;
+        if !response.status().is_success() {
+            return Err(RunpodError::NotificationFailed(format!(
+                "webhook {} returned {}",
+                url,
+                response.status()
+            )));
+        }
+        Ok(())
+    }
+
+    async fn run_command(&self, command: &str, notification: &Notification) -> Result<()> {
+        let mut child = tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .env("RUNPOD_TRIGGER", notification.trigger.to_string())
+            .env("RUNPOD_MESSAGE", &notification.message)
+            .env(
+                "RUNPOD_POD_ID",
+                notification.pod_id.as_deref().unwrap_or_default(),
+            )
+            .env(
+                "RUNPOD_POD_NAME",
+                notification.pod_name.as_deref().unwrap_or_default(),
+            )
+            .env("RUNPOD_NOTIFICATION", serde_json::to_string(notification)?)
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| {
+                RunpodError::NotificationFailed(format!("command failed to start: {e}"))
+            })?;
+        let status = match tokio::time::timeout(self.config.timeout, child.wait()).await {
+            Ok(status) => status?,
+            Err(_) => {
+                let _ = child.kill().await;
+                return Err(RunpodError::NotificationFailed(format!(
+                    "command still running after {}, killed",
+                    humantime::format_duration(self.config.timeout)
+                )));
+            }
+        };
+        if !status.success() {
+            return Err(RunpodError::NotificationFailed(format!(
+                "command exited with {status}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn event_notification(rule: &NotifyRule, event: &PodEvent) -> Option<Notification> {
+    let pod = event.pod()?;
+    if !rule.matches(pod) {
+        return None;
+    }
+    let reason = pod
+        .last_status_change
+        .as_deref()
+        .map(|s| format!(": {s}"))
+        .unwrap_or_default();
+    match (rule.trigger, event) {
+        (Trigger::Preempted, PodEvent::Preempted { .. }) => Some(Notification::for_pod(
+            rule,
+            pod,
+            format!("Spot pod {} ({}) was preempted{reason}", pod.name, pod.id),
+        )),
+        (Trigger::PodDied, PodEvent::StatusChanged { from, .. })
+            if *from == PodStatus::Running
+                && matches!(
+                    pod.desired_status,
+                    PodStatus::Exited | PodStatus::Dead | PodStatus::Terminated
+                ) =>
+        {
+            Some(Notification::for_pod(
+                rule,
+                pod,
+                format!(
+                    "Pod {} ({}) went from {:?} to {:?}{reason}",
+                    pod.name, pod.id, from, pod.desired_status
+                ),
+            ))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::types::{GpuTelemetry, PodRuntime, PodTelemetry, PodType};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn notifier(rules: &str) -> Notifier {
+        let client = RunpodClient::new(Config::new("test-api-key", "http://localhost/graphql"));
+        Notifier::new(client, NotifyConfig::from_toml(rules).unwrap())
+    }
+
+    fn pod(id: &str, status: PodStatus, util: f64) -> Pod {
+        Pod {
+            desired_status: status,
+            pod_type: Some(PodType::Interruptable),
+            runtime: Some(PodRuntime {
+                uptime_in_seconds: Some(7200),
+            }),
+            latest_telemetry: Some(PodTelemetry {
+                state: None,
+                time: None,
+                cpu_utilization: None,
+                memory_utilization: None,
+                average_gpu_metrics: Some(GpuTelemetry {
+                    id: None,
+                    percent_utilization: Some(util),
+                    temperature_celcius: None,
+                    memory_utilization: None,
+                    power_watts: None,
+                }),
+            }),
+            ..Pod::sample(id, id)
+        }
+    }
+
+    #[test]
+    fn rejects_rules_without_action() {
+        assert!(NotifyConfig::from_toml("[[rule]]\ntrigger = \"preempted\"").is_err());
+        assert!(
+            NotifyConfig::from_toml("[[rule]]\ntrigger = \"idle\"\ncommand = \"true\"").is_err()
+        );
+    }
+
+    #[test]
+    fn fires_on_preemption_idle_and_low_balance() {
+        let mut notifier = notifier(
+            r#"
+            interval = "30s"
+
+            [[rule]]
+            trigger = "preempted"
+            command = "true"
+
+            [[rule]]
+            trigger = "idle"
+            idle = "1h"
+            command = "true"
+
+            [[rule]]
+            trigger = "low_balance"
+            below = 10.0
+            command = "true"
+            "#,
+        );
+        assert_eq!(notifier.interval(), Duration::from_secs(30));
+        let now = Instant::now();
+        let summary = |balance| AccountSummary {
+            current_spend_per_hr: None,
+            client_balance: Some(balance),
+            spend_limit: None,
+            under_balance: None,
+            credit_alert_threshold: None,
+            spend_details: None,
+        };
+
+        let fired = notifier.evaluate(
+            vec![
+                pod("a", PodStatus::Running, 90.0),
+                pod("b", PodStatus::Running, 0.0),
+            ],
+            Some(&summary(5.0)),
+            now,
+        );
+        let triggers: Vec<_> = fired.iter().map(|(_, n)| n.trigger).collect();
+        assert_eq!(triggers, vec![Trigger::Idle, Trigger::LowBalance]);
+
+        // Already notified: nothing new until something changes.
+        let preempted = Pod {
+            last_status_change: Some("Exited by Runpod: spot instance preempted".into()),
+            ..pod("a", PodStatus::Exited, 0.0)
+        };
+        let fired = notifier.evaluate(
+            vec![preempted, pod("b", PodStatus::Running, 0.0)],
+            Some(&summary(5.0)),
+            now,
+        );
+        let fired: Vec<_> = fired.into_iter().map(|(_, n)| n).collect();
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].trigger, Trigger::Preempted);
+        assert_eq!(fired[0].pod_id.as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn user_stop_is_not_preemption() {
+        let mut notifier = notifier(
+            r#"
+            [[rule]]
+            trigger = "preempted"
+            command = "true"
+            "#,
+        );
+        let now = Instant::now();
+        notifier.evaluate(vec![pod("a", PodStatus::Running, 90.0)], None, now);
+        let stopped = Pod {
+            last_status_change: Some("Exited by User".into()),
+            ..pod("a", PodStatus::Exited, 0.0)
+        };
+        assert!(notifier.evaluate(vec![stopped], None, now).is_empty());
+    }
+
+    #[tokio::test]
+    async fn posts_json_to_webhook() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            loop {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|l| {
+                            l.to_ascii_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if body.len() >= length {
+                        break;
+                    }
+                }
+            }
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let notifier = notifier(&format!(
+            "[[rule]]\nname = \"spot\"\ntrigger = \"preempted\"\nwebhook = \"http://{addr}/hook\""
+        ));
+        let rule = notifier.config.rules[0].clone();
+        let notification = Notification::for_pod(
+            &rule,
+            &pod("p1", PodStatus::Exited, 0.0),
+            "preempted".into(),
+        );
+        notifier.send(&rule, &notification).await.unwrap();
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /hook "));
+        let body: serde_json::Value =
+            serde_json::from_str(request.split_once("\r\n\r\n").unwrap().1).unwrap();
+        assert_eq!(body["rule"], "spot");
+        assert_eq!(body["trigger"], "preempted");
+        assert_eq!(body["pod_id"], "p1");
+    }
+
+    #[tokio::test]
+    async fn hung_deliveries_time_out() {
+        // Accepts the connection but never answers.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let _server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            drop(socket);
+        });
+
+        let notifier = notifier(&format!(
+            "timeout = \"200ms\"\n\
+             [[rule]]\nname = \"hook\"\ntrigger = \"preempted\"\nwebhook = \"http://{addr}/hook\"\n\
+             [[rule]]\nname = \"cmd\"\ntrigger = \"preempted\"\ncommand = \"sleep 30\""
+        ));
+        let started = std::time::Instant::now();
+        for rule in notifier.config.rules.clone() {
+            let notification = Notification::for_pod(
+                &rule,
+                &pod("p1", PodStatus::Exited, 0.0),
+                "preempted".into(),
+            );
+            let err = notifier.send(&rule, &notification).await.unwrap_err();
+            assert!(matches!(err, RunpodError::NotificationFailed(_)), "{err}");
+        }
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+}
@@ -0,0 +1,320 @@
+use crate::error::Result;
+use crate::types::{Pod, PodStatus};
+use crate::RunpodClient;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+use typed_builder::TypedBuilder;
+use wildmatch::WildMatch;
+
+/// What to do with a pod once it has been idle for too long.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReapAction {
+    Stop,
+    Terminate,
+}
+
+impl std::fmt::Display for ReapAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReapAction::Stop => write!(f, "stop"),
+            ReapAction::Terminate => write!(f, "terminate"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, TypedBuilder)]
+pub struct ReaperConfig {
+    /// How long a pod must have been idle before it is reaped.
+    pub idle_threshold: Duration,
+    /// A pod counts as idle while its average GPU utilization is at or below this percentage.
+    #[builder(default = 0.0)]
+    pub gpu_utilization_threshold: f64,
+    #[builder(default = ReapAction::Stop)]
+    pub action: ReapAction,
+    /// Name patterns (`*` and `?` wildcards) a pod must match to be considered. Empty means all pods.
+    #[builder(default)]
+    pub allow: Vec<String>,
+    /// Name patterns for pods that are never reaped. Takes precedence over `allow`.
+    #[builder(default)]
+    pub deny: Vec<String>,
+    #[builder(default = false)]
+    pub dry_run: bool,
+    /// Treat a pod that is idle the first time it is seen as idle for its whole uptime.
+    /// Without this, a pod is only reaped once it has stayed idle across passes for
+    /// `idle_threshold`, so a single pass never reaps anything.
+    #[builder(default = false)]
+    pub assume_idle_since_start: bool,
+}
+
+impl ReaperConfig {
+    fn is_eligible(&self, name: &str) -> bool {
+        if self.deny.iter().any(|p| WildMatch::new(p).matches(name)) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|p| WildMatch::new(p).matches(name))
+    }
+
+    fn is_idle(&self, pod: &Pod) -> bool {
+        pod.gpu_count > 0
+            && pod
+                .gpu_utilization()
+                .is_some_and(|util| util <= self.gpu_utilization_threshold)
+    }
+}
+
+/// A pod selected by the reaper and what happened to it.
+#[derive(Debug, Clone)]
+pub struct ReapOutcome {
+    pub pod_id: String,
+    pub pod_name: String,
+    pub idle_for: Duration,
+    pub adjusted_cost_per_hr: f64,
+    pub action: ReapAction,
+    /// `false` for dry runs and for pods where the action failed.
+    pub applied: bool,
+}
+
+/// Stops or terminates pods whose GPUs have been idle for longer than a threshold.
+///
+/// The API only exposes the latest telemetry sample, so a pod's idle time is measured
+/// from the first pass that saw it idle. With
+/// [`assume_idle_since_start`](ReaperConfig::assume_idle_since_start), a pod that is
+/// idle the first time it is seen counts as idle for its whole uptime instead.
+#[derive(Debug)]
+pub struct Reaper {
+    client: RunpodClient,
+    config: ReaperConfig,
+    seen: HashSet<String>,
+    idle_since: HashMap<String, Instant>,
+}
+
+impl Reaper {
+    pub fn new(client: RunpodClient, config: ReaperConfig) -> Self {
+        Self {
+            client,
+            config,
+            seen: HashSet::new(),
+            idle_since: HashMap::new(),
+        }
+    }
+
+    /// Run a single pass over the current pods.
+    pub async fn run_once(&mut self) -> Result<Vec<ReapOutcome>> {
+        let pods = self.client.list_pods().await?;
+        let mut outcomes = self.select(&pods, Instant::now());
+
+        for outcome in outcomes.iter_mut() {
+            if self.config.dry_run {
+                info!(
+                    "[dry-run] would {} pod {} ({}), idle for {}s",
+                    outcome.action,
+                    outcome.pod_name,
+                    outcome.pod_id,
+                    outcome.idle_for.as_secs()
+                );
+                continue;
+            }
+            let res = match outcome.action {
+                ReapAction::Stop => self.client.stop_pod(&outcome.pod_id).await.map(|_| ()),
+                ReapAction::Terminate => self.client.terminate_pod(&outcome.pod_id).await,
+            };
+            match res {
+                Ok(()) => {
+                    info!(
+                        "Reaped pod {} ({}) with {}",
+                        outcome.pod_name, outcome.pod_id, outcome.action
+                    );
+                    outcome.applied = true;
+                    self.idle_since.remove(&outcome.pod_id);
+                }
+                Err(e) => error!(
+                    "Failed to {} pod {} ({}): {}",
+                    outcome.action, outcome.pod_name, outcome.pod_id, e
+                ),
+            }
+        }
+        Ok(outcomes)
+    }
+
+    /// Run passes forever, sleeping `interval` between them. Errors from a pass are logged
+    /// and the loop carries on.
+    pub async fn run(&mut self, interval: Duration) -> Result<()> {
+        loop {
+            if let Err(e) = self.run_once().await {
+                warn!("Reaper pass failed: {}", e);
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    pub(crate) fn select(&mut self, pods: &[Pod], now: Instant) -> Vec<ReapOutcome> {
+        let mut outcomes = Vec::new();
+        let live: HashSet<&str> = pods.iter().map(|p| p.id.as_str()).collect();
+        self.idle_since.retain(|id, _| live.contains(id.as_str()));
+        self.seen.retain(|id| live.contains(id.as_str()));
+
+        for pod in pods {
+            let first_seen = self.seen.insert(pod.id.clone());
+            if pod.desired_status != PodStatus::Running || !self.config.is_idle(pod) {
+                self.idle_since.remove(&pod.id);
+                continue;
+            }
+
+            let uptime = Duration::from_secs(pod.uptime_in_seconds().unwrap_or(0).max(0) as u64);
+            let since = *self.idle_since.entry(pod.id.clone()).or_insert_with(|| {
+                if first_seen && self.config.assume_idle_since_start {
+                    now.checked_sub(uptime).unwrap_or(now)
+                } else {
+                    now
+                }
+            });
+            let idle_for = now.duration_since(since).min(uptime);
+
+            if idle_for < self.config.idle_threshold || !self.config.is_eligible(&pod.name) {
+                continue;
+            }
+            outcomes.push(ReapOutcome {
+                pod_id: pod.id.clone(),
+                pod_name: pod.name.clone(),
+                idle_for,
+                adjusted_cost_per_hr: pod.adjusted_cost_per_hr,
+                action: self.config.action,
+                applied: false,
+            });
+        }
+        outcomes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::types::{GpuTelemetry, PodRuntime, PodTelemetry};
+
+    fn pod(id: &str, name: &str, util: f64, uptime: i64) -> Pod {
+        Pod {
+            runtime: Some(PodRuntime {
+                uptime_in_seconds: Some(uptime),
+            }),
+            latest_telemetry: Some(PodTelemetry {
+                state: None,
+                time: None,
+                cpu_utilization: None,
+                memory_utilization: None,
+                average_gpu_metrics: Some(GpuTelemetry {
+                    id: None,
+                    percent_utilization: Some(util),
+                    temperature_celcius: None,
+                    memory_utilization: None,
+                    power_watts: None,
+                }),
+            }),
+            ..Pod::sample(id, name)
+        }
+    }
+
+    fn reaper(config: ReaperConfig) -> Reaper {
+        let client = RunpodClient::new(Config::new("test-api-key", "http://localhost/graphql"));
+        Reaper::new(client, config)
+    }
+
+    #[test]
+    fn selects_idle_pods_past_threshold() {
+        let mut reaper = reaper(
+            ReaperConfig::builder()
+                .idle_threshold(Duration::from_secs(3600))
+                .assume_idle_since_start(true)
+                .build(),
+        );
+        let pods = vec![
+            pod("a", "idle-long", 0.0, 7200),
+            pod("b", "idle-short", 0.0, 60),
+            pod("c", "busy", 95.0, 7200),
+        ];
+        let ids: Vec<_> = reaper
+            .select(&pods, Instant::now())
+            .into_iter()
+            .map(|o| o.pod_id)
+            .collect();
+        assert_eq!(ids, vec!["a"]);
+    }
+
+    #[test]
+    fn deny_overrides_allow() {
+        let mut reaper = reaper(
+            ReaperConfig::builder()
+                .idle_threshold(Duration::from_secs(60))
+                .allow(vec!["exp-*".into()])
+                .assume_idle_since_start(true)
+                .deny(vec!["exp-keep*".into()])
+                .build(),
+        );
+        let pods = vec![
+            pod("a", "exp-1", 0.0, 600),
+            pod("b", "exp-keep-me", 0.0, 600),
+            pod("c", "prod", 0.0, 600),
+        ];
+        let ids: Vec<_> = reaper
+            .select(&pods, Instant::now())
+            .into_iter()
+            .map(|o| o.pod_id)
+            .collect();
+        assert_eq!(ids, vec!["a"]);
+    }
+
+    #[test]
+    fn busy_pod_restarts_idle_clock() {
+        let mut reaper = reaper(
+            ReaperConfig::builder()
+                .idle_threshold(Duration::from_secs(600))
+                .build(),
+        );
+        let start = Instant::now();
+        assert!(reaper
+            .select(&[pod("a", "p", 90.0, 7200)], start)
+            .is_empty());
+        // Went idle after being seen busy: timed from this pass, not from uptime.
+        assert!(reaper
+            .select(&[pod("a", "p", 0.0, 7260)], start + Duration::from_secs(60))
+            .is_empty());
+        assert_eq!(
+            reaper
+                .select(
+                    &[pod("a", "p", 0.0, 7920)],
+                    start + Duration::from_secs(720)
+                )
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn first_sighting_does_not_count_uptime() {
+        let mut reaper = reaper(
+            ReaperConfig::builder()
+                .idle_threshold(Duration::from_secs(600))
+                .action(ReapAction::Terminate)
+                .build(),
+        );
+        let start = Instant::now();
+        // Up for two hours and idle right now, but nothing says it was idle before.
+        assert!(reaper.select(&[pod("a", "p", 0.0, 7200)], start).is_empty());
+        assert!(reaper
+            .select(
+                &[pod("a", "p", 0.0, 7500)],
+                start + Duration::from_secs(300)
+            )
+            .is_empty());
+        assert_eq!(
+            reaper
+                .select(
+                    &[pod("a", "p", 0.0, 7800)],
+                    start + Duration::from_secs(600)
+                )
+                .len(),
+            1
+        );
+    }
+}
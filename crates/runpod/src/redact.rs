@@ -0,0 +1,131 @@
+//! Scrubbing of sensitive GraphQL fields before request and response bodies are logged.
+
+use serde_json::Value;
+
+pub const REDACTED: &str = "[REDACTED]";
+
+/// GraphQL field names whose values are never logged: environment variables (which often
+/// hold tokens), secret values, registry credentials and pod API keys.
+pub const SENSITIVE_FIELDS: &[&str] = &[
+    "env",
+    "value",
+    "password",
+    "pass",
+    "auth",
+    "registryAuth",
+    "apiKey",
+    "volumeKey",
+    "authorization",
+];
+
+fn is_sensitive(field: &str) -> bool {
+    SENSITIVE_FIELDS
+        .iter()
+        .any(|s| s.eq_ignore_ascii_case(field))
+}
+
+/// Replace the value of every [`SENSITIVE_FIELDS`] entry in `value`, at any depth.
+pub fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                if is_sensitive(key) && !field.is_null() {
+                    *field = Value::String(REDACTED.to_string());
+                } else {
+                    redact(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+/// Like [`redact`], but keeps the shape of the document: every string under a sensitive
+/// field is replaced while arrays, objects, numbers and nulls stay as they are, so the
+/// result still deserializes into the same types.
+pub fn scrub(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                if is_sensitive(key) {
+                    scrub_strings(field);
+                } else {
+                    scrub(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(scrub),
+        _ => {}
+    }
+}
+
+fn scrub_strings(value: &mut Value) {
+    match value {
+        Value::String(s) => *s = REDACTED.to_string(),
+        Value::Object(map) => map.values_mut().for_each(scrub_strings),
+        Value::Array(items) => items.iter_mut().for_each(scrub_strings),
+        _ => {}
+    }
+}
+
+/// A redacted copy of `value`.
+pub fn redacted(value: &Value) -> Value {
+    let mut value = value.clone();
+    redact(&mut value);
+    value
+}
+
+/// Redact a raw JSON body for logging. Bodies that are not valid JSON are replaced
+/// entirely, since there is no way to tell what they contain.
+pub fn redact_body(body: &[u8]) -> String {
+    match serde_json::from_slice::<Value>(body) {
+        Ok(mut value) => {
+            redact(&mut value);
+            value.to_string()
+        }
+        Err(_) => format!("<{} bytes of non-JSON body>", body.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn redacts_nested_fields() {
+        let mut body = json!({
+            "operationName": "SpawnPodOnDemand",
+            "variables": {
+                "input": {
+                    "name": "exp",
+                    "env": [{"key": "HF_TOKEN", "value": "hf_abc"}],
+                }
+            },
+            "data": {"pod": {"id": "p1", "apiKey": "rp_123", "registry": {"pass": "pw", "url": "ghcr.io"}}},
+        });
+        redact(&mut body);
+        assert_eq!(body["variables"]["input"]["name"], "exp");
+        assert_eq!(body["variables"]["input"]["env"], REDACTED);
+        assert_eq!(body["data"]["pod"]["apiKey"], REDACTED);
+        assert_eq!(body["data"]["pod"]["registry"]["pass"], REDACTED);
+        assert_eq!(body["data"]["pod"]["registry"]["url"], "ghcr.io");
+    }
+
+    #[test]
+    fn scrub_keeps_shape() {
+        let mut body = json!({
+            "env": ["HF_TOKEN=hf_abc"],
+            "pod": {"env": [{"key": "A", "value": "b"}], "gpuCount": 1, "volumeKey": null},
+        });
+        scrub(&mut body);
+        assert_eq!(body["env"], json!([REDACTED]));
+        assert_eq!(
+            body["pod"]["env"],
+            json!([{"key": REDACTED, "value": REDACTED}])
+        );
+        assert_eq!(body["pod"]["gpuCount"], 1);
+        assert!(body["pod"]["volumeKey"].is_null());
+    }
+}
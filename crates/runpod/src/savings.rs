@@ -0,0 +1,272 @@
+use crate::budget::estimate_cost_per_hr;
+use crate::gql::{list_savings_plans, spawn_pod_on_demand, DateTime};
+use crate::types::GpuOffer;
+use crate::RunpodError;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Commitment length of a savings plan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SavingsPlanLength {
+    OneWeek,
+    OneMonth,
+    ThreeMonths,
+    SixMonths,
+}
+
+impl SavingsPlanLength {
+    pub const ALL: [SavingsPlanLength; 4] = [
+        SavingsPlanLength::OneWeek,
+        SavingsPlanLength::OneMonth,
+        SavingsPlanLength::ThreeMonths,
+        SavingsPlanLength::SixMonths,
+    ];
+
+    /// Number of hours covered by the plan.
+    pub fn hours(&self) -> f64 {
+        let days = match self {
+            SavingsPlanLength::OneWeek => 7,
+            SavingsPlanLength::OneMonth => 30,
+            SavingsPlanLength::ThreeMonths => 90,
+            SavingsPlanLength::SixMonths => 180,
+        };
+        (days * 24) as f64
+    }
+
+    /// Discounted hourly price per GPU for this plan length.
+    pub fn price_per_gpu(&self, offer: &GpuOffer) -> Option<f64> {
+        match self {
+            SavingsPlanLength::OneWeek => offer.one_week_price,
+            SavingsPlanLength::OneMonth => offer.one_month_price,
+            SavingsPlanLength::ThreeMonths => offer.three_month_price,
+            SavingsPlanLength::SixMonths => offer.six_month_price,
+        }
+    }
+}
+
+/// The `planLength` value sent to the API. These strings are guesses: the schema types the
+/// field as a plain `String` and no recorded response confirms them.
+impl Display for SavingsPlanLength {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SavingsPlanLength::OneWeek => write!(f, "1w"),
+            SavingsPlanLength::OneMonth => write!(f, "1m"),
+            SavingsPlanLength::ThreeMonths => write!(f, "3m"),
+            SavingsPlanLength::SixMonths => write!(f, "6m"),
+        }
+    }
+}
+
+impl FromStr for SavingsPlanLength {
+    type Err = RunpodError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "1w" | "week" => Ok(SavingsPlanLength::OneWeek),
+            "1m" | "month" => Ok(SavingsPlanLength::OneMonth),
+            "3m" => Ok(SavingsPlanLength::ThreeMonths),
+            "6m" => Ok(SavingsPlanLength::SixMonths),
+            other => Err(RunpodError::InvalidInput(format!(
+                "unknown savings plan length: {other} (expected 1w, 1m, 3m or 6m)"
+            ))),
+        }
+    }
+}
+
+/// A savings plan to buy when deploying an on-demand pod.
+///
+/// The purchase is unverified: the `planLength` string sent for [`Self::plan_length`] is
+/// a guess, and the real upfront amount is left to the API. Spawning refuses a plan unless
+/// [`Self::accept_unverified`] is set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavingsPlanSpec {
+    pub plan_length: SavingsPlanLength,
+    /// Client-side estimate of the upfront payment, see [`SavingsPlanQuote::upfront_cost`].
+    /// Only used for budget checks; it is not sent with the purchase.
+    pub upfront_cost: f64,
+    /// Buy the plan even though the values sent for it have not been checked against the
+    /// API. Off by default.
+    pub accept_unverified: bool,
+}
+
+impl From<SavingsPlanSpec> for spawn_pod_on_demand::SavingsPlanInput {
+    fn from(spec: SavingsPlanSpec) -> Self {
+        Self {
+            plan_length: Some(spec.plan_length.to_string()),
+            upfront_cost: None,
+        }
+    }
+}
+
+/// Cost of a savings plan for some number of GPUs compared with paying on demand.
+#[cfg_attr(feature = "tabled", derive(tabled::Tabled))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavingsPlanQuote {
+    pub plan_length: SavingsPlanLength,
+    #[cfg_attr(feature = "tabled", tabled(display = "display_dollars"))]
+    pub plan_cost_per_hr: f64,
+    #[cfg_attr(feature = "tabled", tabled(display = "display_dollars"))]
+    pub on_demand_cost_per_hr: f64,
+    /// Estimated as the plan's hourly price times [`SavingsPlanLength::hours`], counting a
+    /// month as 30 days. Not a quote from the API.
+    #[cfg_attr(feature = "tabled", tabled(display = "display_dollars"))]
+    pub upfront_cost: f64,
+    /// Hours of on-demand use that would cost as much as the plan. The plan only pays off
+    /// if the GPUs would run for longer than this within the plan period.
+    #[cfg_attr(feature = "tabled", tabled(display = "display_hours"))]
+    pub break_even_hours: f64,
+    /// [`Self::break_even_hours`] as a share of the plan length, from 0 to 1.
+    #[cfg_attr(feature = "tabled", tabled(display = "display_percent"))]
+    pub break_even_utilization: f64,
+}
+
+impl SavingsPlanQuote {
+    /// Quote a plan of the given length for `gpu_count` GPUs of `offer`. Returns `None` if
+    /// the offer has no price for the plan or no on-demand price.
+    pub fn new(offer: &GpuOffer, gpu_count: i64, plan_length: SavingsPlanLength) -> Option<Self> {
+        let plan_cost_per_hr = plan_length.price_per_gpu(offer)? * gpu_count as f64;
+        let on_demand_cost_per_hr = estimate_cost_per_hr(offer, gpu_count, false, None)?;
+        if on_demand_cost_per_hr <= 0.0 {
+            return None;
+        }
+        let upfront_cost = plan_cost_per_hr * plan_length.hours();
+        let break_even_hours = upfront_cost / on_demand_cost_per_hr;
+        Some(Self {
+            plan_length,
+            plan_cost_per_hr,
+            on_demand_cost_per_hr,
+            upfront_cost,
+            break_even_hours,
+            break_even_utilization: break_even_hours / plan_length.hours(),
+        })
+    }
+
+    /// Quotes for every plan length the offer has a price for.
+    pub fn all(offer: &GpuOffer, gpu_count: i64) -> Vec<Self> {
+        SavingsPlanLength::ALL
+            .into_iter()
+            .filter_map(|length| Self::new(offer, gpu_count, length))
+            .collect()
+    }
+
+    /// A spec for buying this plan, not yet opted in to the unverified purchase.
+    pub fn spec(&self) -> SavingsPlanSpec {
+        SavingsPlanSpec {
+            plan_length: self.plan_length,
+            upfront_cost: self.upfront_cost,
+            accept_unverified: false,
+        }
+    }
+}
+
+#[cfg(feature = "tabled")]
+fn display_dollars(v: &f64) -> String {
+    format!("${v:.2}")
+}
+
+#[cfg(feature = "tabled")]
+fn display_hours(v: &f64) -> String {
+    format!("{v:.0}h")
+}
+
+#[cfg(feature = "tabled")]
+fn display_percent(v: &f64) -> String {
+    format!("{:.0}%", v * 100.0)
+}
+
+/// A savings plan bought on the account.
+#[cfg_attr(
+    feature = "tabled",
+    derive(tabled::Tabled),
+    tabled(display(Option, "crate::types::display_option", ""))
+)]
+#[derive(Debug, Clone)]
+pub struct SavingsPlan {
+    pub gpu_type_id: Option<String>,
+    pub pod_id: Option<String>,
+    pub plan_length: Option<String>,
+    pub savings_plan_type: Option<String>,
+    pub cost_per_hr: Option<f64>,
+    pub upfront_cost: Option<f64>,
+    pub start_time: Option<DateTime>,
+    pub end_time: Option<DateTime>,
+}
+
+impl From<list_savings_plans::ListSavingsPlansMyselfSavingsPlans> for SavingsPlan {
+    fn from(plan: list_savings_plans::ListSavingsPlansMyselfSavingsPlans) -> Self {
+        Self {
+            gpu_type_id: plan.gpu_type_id,
+            pod_id: plan.pod_id,
+            plan_length: plan.plan_length,
+            savings_plan_type: plan.savings_plan_type,
+            cost_per_hr: plan.cost_per_hr,
+            upfront_cost: plan.upfront_cost,
+            start_time: plan.start_time,
+            end_time: plan.end_time,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offer(on_demand: f64, one_month: f64) -> GpuOffer {
+        GpuOffer {
+            id: "NVIDIA A100 80GB PCIe".into(),
+            display_name: "A100".into(),
+            manufacturer: None,
+            memory_in_gb: Some(80),
+            secure_cloud: Some(true),
+            community_cloud: Some(false),
+            secure_price: Some(on_demand),
+            community_price: None,
+            one_month_price: Some(one_month),
+            three_month_price: None,
+            six_month_price: None,
+            one_week_price: None,
+            community_spot_price: None,
+            secure_spot_price: None,
+            max_gpu_count: Some(8),
+            max_gpu_count_community_cloud: None,
+            max_gpu_count_secure_cloud: Some(8),
+            min_pod_gpu_count: None,
+            lowest_price: None,
+        }
+    }
+
+    #[test]
+    fn computes_break_even() {
+        let quotes = SavingsPlanQuote::all(&offer(2.0, 1.5), 2);
+        assert_eq!(quotes.len(), 1);
+        let quote = &quotes[0];
+        assert_eq!(quote.plan_length, SavingsPlanLength::OneMonth);
+        assert_eq!(quote.plan_cost_per_hr, 3.0);
+        assert_eq!(quote.on_demand_cost_per_hr, 4.0);
+        assert_eq!(quote.upfront_cost, 3.0 * 720.0);
+        assert_eq!(quote.break_even_hours, 540.0);
+        assert_eq!(quote.break_even_utilization, 0.75);
+    }
+
+    #[tokio::test]
+    async fn purchase_needs_opt_in_and_sends_no_upfront_cost() {
+        let spec = SavingsPlanQuote::all(&offer(2.0, 1.5), 1)[0].spec();
+        let input = spawn_pod_on_demand::SavingsPlanInput::from(spec.clone());
+        assert_eq!(input.upfront_cost, None);
+
+        let client = crate::RunpodClient::new(crate::config::Config::new(
+            "unused",
+            "http://localhost/graphql",
+        ));
+        let pod = crate::types::SpawnPodSpec::builder()
+            .name("train")
+            .gpu_type_id("NVIDIA A100 80GB PCIe")
+            .template_id("tpl")
+            .savings_plan(spec)
+            .build();
+        assert!(matches!(
+            client.spawn_pod(pod).await,
+            Err(RunpodError::InvalidInput(_))
+        ));
+    }
+}
@@ -0,0 +1,782 @@
+//! Keeping `gql/schema.graphql` in step with the live API.
+//!
+//! The vendored schema is a hand-maintained snapshot. It has never been regenerated from
+//! introspection, and parts of it were written by hand (the secret mutations, `saveTemplate`
+//! and the registry auth inputs), so they may not match the live API until `runpod schema
+//! diff` has been run against it. [`RunpodClient::fetch_schema`]
+//! introspects the live API and renders it as SDL, and [`diff`] compares two schemas and
+//! reports which of the changes touch fields this crate's `.graphql` documents use.
+//!
+//! [`RunpodClient::fetch_schema`]: crate::RunpodClient::fetch_schema
+
+use crate::error::Result;
+use crate::RunpodError;
+use graphql_parser::query::{self as q, Selection, TypeCondition};
+use graphql_parser::schema::{
+    self as s, Definition, EnumType, EnumValue, InputObjectType, InputValue, InterfaceType,
+    ObjectType, ScalarType, SchemaDefinition, TypeDefinition, UnionType,
+};
+use graphql_parser::Pos;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Display;
+
+/// The schema this crate's documents are compiled against.
+pub const VENDORED_SCHEMA: &str = include_str!("../gql/schema.graphql");
+
+/// The crate's GraphQL documents, by file name.
+pub const DOCUMENTS: &[(&str, &str)] = &[
+    (
+        "account_summary.graphql",
+        include_str!("../gql/account_summary.graphql"),
+    ),
+    ("api_keys.graphql", include_str!("../gql/api_keys.graphql")),
+    (
+        "audit_logs.graphql",
+        include_str!("../gql/audit_logs.graphql"),
+    ),
+    ("billing.graphql", include_str!("../gql/billing.graphql")),
+    ("get_pod.graphql", include_str!("../gql/get_pod.graphql")),
+    (
+        "get_templates.graphql",
+        include_str!("../gql/get_templates.graphql"),
+    ),
+    (
+        "gpu_types.graphql",
+        include_str!("../gql/gpu_types.graphql"),
+    ),
+    ("host.graphql", include_str!("../gql/host.graphql")),
+    (
+        "myself_query.graphql",
+        include_str!("../gql/myself_query.graphql"),
+    ),
+    (
+        "registry_auth.graphql",
+        include_str!("../gql/registry_auth.graphql"),
+    ),
+    (
+        "save_template.graphql",
+        include_str!("../gql/save_template.graphql"),
+    ),
+    (
+        "savings_plans.graphql",
+        include_str!("../gql/savings_plans.graphql"),
+    ),
+    ("secrets.graphql", include_str!("../gql/secrets.graphql")),
+    (
+        "spawn_pod.graphql",
+        include_str!("../gql/spawn_pod.graphql"),
+    ),
+    ("stop_pod.graphql", include_str!("../gql/stop_pod.graphql")),
+    ("teams.graphql", include_str!("../gql/teams.graphql")),
+    (
+        "terminate_pod.graphql",
+        include_str!("../gql/terminate_pod.graphql"),
+    ),
+];
+
+/// The standard introspection query, without directives.
+pub(crate) const INTROSPECTION_QUERY: &str = r#"
+query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    subscriptionType { name }
+    types { ...FullType }
+  }
+}
+
+fragment FullType on __Type {
+  kind
+  name
+  description
+  fields(includeDeprecated: true) {
+    name
+    description
+    args { ...InputValue }
+    type { ...TypeRef }
+  }
+  inputFields { ...InputValue }
+  interfaces { ...TypeRef }
+  enumValues(includeDeprecated: true) { name description }
+  possibleTypes { ...TypeRef }
+}
+
+fragment InputValue on __InputValue {
+  name
+  description
+  type { ...TypeRef }
+}
+
+fragment TypeRef on __Type {
+  kind
+  name
+  ofType { kind name ofType { kind name ofType { kind name ofType { kind name } } } }
+}
+"#;
+
+const BUILTIN_SCALARS: &[&str] = &["String", "Int", "Float", "Boolean", "ID"];
+
+fn schema_error(message: impl Display) -> RunpodError {
+    RunpodError::SchemaError(message.to_string())
+}
+
+fn type_ref(value: &Value) -> Result<s::Type<'static, String>> {
+    let of_type = || type_ref(&value["ofType"]).map(Box::new);
+    match value["kind"].as_str() {
+        Some("NON_NULL") => Ok(s::Type::NonNullType(of_type()?)),
+        Some("LIST") => Ok(s::Type::ListType(of_type()?)),
+        _ => value["name"]
+            .as_str()
+            .map(|name| s::Type::NamedType(name.to_string()))
+            .ok_or_else(|| schema_error(format!("type reference without a name: {value}"))),
+    }
+}
+
+fn description(value: &Value) -> Option<String> {
+    value["description"]
+        .as_str()
+        .filter(|d| !d.is_empty())
+        .map(str::to_string)
+}
+
+fn names(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v["name"].as_str().map(str::to_string))
+        .collect()
+}
+
+fn input_values(value: &Value) -> Result<Vec<InputValue<'static, String>>> {
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|v| {
+            Ok(InputValue {
+                position: Pos::default(),
+                description: description(v),
+                name: v["name"].as_str().unwrap_or_default().to_string(),
+                value_type: type_ref(&v["type"])?,
+                default_value: None,
+                directives: vec![],
+            })
+        })
+        .collect()
+}
+
+fn fields(value: &Value) -> Result<Vec<s::Field<'static, String>>> {
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|v| {
+            Ok(s::Field {
+                position: Pos::default(),
+                description: description(v),
+                name: v["name"].as_str().unwrap_or_default().to_string(),
+                arguments: input_values(&v["args"])?,
+                field_type: type_ref(&v["type"])?,
+                directives: vec![],
+            })
+        })
+        .collect()
+}
+
+/// Render the `data` of an introspection query as SDL. Argument default values are not
+/// carried over.
+pub fn sdl_from_introspection(data: &Value) -> Result<String> {
+    let schema = &data["__schema"];
+    let types = schema["types"]
+        .as_array()
+        .ok_or_else(|| schema_error("introspection result has no types"))?;
+
+    let mut definitions = vec![Definition::SchemaDefinition(SchemaDefinition {
+        position: Pos::default(),
+        directives: vec![],
+        query: schema["queryType"]["name"].as_str().map(str::to_string),
+        mutation: schema["mutationType"]["name"].as_str().map(str::to_string),
+        subscription: schema["subscriptionType"]["name"]
+            .as_str()
+            .map(str::to_string),
+    })];
+    for ty in types {
+        let name = ty["name"].as_str().unwrap_or_default().to_string();
+        if name.starts_with("__") || BUILTIN_SCALARS.contains(&name.as_str()) {
+            continue;
+        }
+        let definition = match ty["kind"].as_str() {
+            Some("SCALAR") => TypeDefinition::Scalar(ScalarType {
+                description: description(ty),
+                ..ScalarType::new(name)
+            }),
+            Some("OBJECT") => TypeDefinition::Object(ObjectType {
+                description: description(ty),
+                implements_interfaces: names(&ty["interfaces"]),
+                fields: fields(&ty["fields"])?,
+                ..ObjectType::new(name)
+            }),
+            Some("INTERFACE") => TypeDefinition::Interface(InterfaceType {
+                description: description(ty),
+                implements_interfaces: names(&ty["interfaces"]),
+                fields: fields(&ty["fields"])?,
+                ..InterfaceType::new(name)
+            }),
+            Some("UNION") => TypeDefinition::Union(UnionType {
+                description: description(ty),
+                types: names(&ty["possibleTypes"]),
+                ..UnionType::new(name)
+            }),
+            Some("ENUM") => TypeDefinition::Enum(EnumType {
+                description: description(ty),
+                values: ty["enumValues"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|v| EnumValue {
+                        description: description(v),
+                        ..EnumValue::new(v["name"].as_str().unwrap_or_default().to_string())
+                    })
+                    .collect(),
+                ..EnumType::new(name)
+            }),
+            Some("INPUT_OBJECT") => TypeDefinition::InputObject(InputObjectType {
+                description: description(ty),
+                fields: input_values(&ty["inputFields"])?,
+                ..InputObjectType::new(name)
+            }),
+            other => return Err(schema_error(format!("unknown type kind {other:?}"))),
+        };
+        definitions.push(Definition::TypeDefinition(definition));
+    }
+    Ok(s::Document { definitions }.to_string())
+}
+
+/// A field, input field, enum value or argument list of one type, reduced to what a
+/// diff compares.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FieldShape {
+    /// The field's type, empty for enum values and union members.
+    ty: String,
+    /// The named type inside `ty`'s list and non-null wrappers.
+    named: String,
+    /// Argument name to type.
+    args: BTreeMap<String, String>,
+}
+
+impl Display for FieldShape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.args.is_empty() {
+            return f.write_str(&self.ty);
+        }
+        let args: Vec<_> = self.args.iter().map(|(n, t)| format!("{n}: {t}")).collect();
+        write!(f, "({}): {}", args.join(", "), self.ty)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TypeShape {
+    kind: &'static str,
+    fields: BTreeMap<String, FieldShape>,
+}
+
+/// A parsed schema, indexed by type and field name.
+#[derive(Debug, Clone)]
+pub struct SchemaIndex {
+    types: BTreeMap<String, TypeShape>,
+    query: String,
+    mutation: String,
+}
+
+fn named_type<'a>(ty: &'a s::Type<'_, String>) -> &'a str {
+    match ty {
+        s::Type::NamedType(name) => name,
+        s::Type::ListType(inner) | s::Type::NonNullType(inner) => named_type(inner),
+    }
+}
+
+impl FieldShape {
+    fn new(ty: &s::Type<'_, String>, args: &[InputValue<'_, String>]) -> Self {
+        Self {
+            ty: ty.to_string(),
+            named: named_type(ty).to_string(),
+            args: args
+                .iter()
+                .map(|a| (a.name.clone(), a.value_type.to_string()))
+                .collect(),
+        }
+    }
+
+    fn member() -> Self {
+        Self {
+            ty: String::new(),
+            named: String::new(),
+            args: BTreeMap::new(),
+        }
+    }
+}
+
+fn field_shapes(fields: &[s::Field<'_, String>]) -> BTreeMap<String, FieldShape> {
+    fields
+        .iter()
+        .map(|f| (f.name.clone(), FieldShape::new(&f.field_type, &f.arguments)))
+        .collect()
+}
+
+fn members<'a>(names: impl IntoIterator<Item = &'a String>) -> BTreeMap<String, FieldShape> {
+    names
+        .into_iter()
+        .map(|name| (name.clone(), FieldShape::member()))
+        .collect()
+}
+
+impl SchemaIndex {
+    pub fn parse(sdl: &str) -> Result<Self> {
+        let document = graphql_parser::parse_schema::<String>(sdl).map_err(schema_error)?;
+        let mut index = SchemaIndex {
+            types: BTreeMap::new(),
+            query: "Query".into(),
+            mutation: "Mutation".into(),
+        };
+        for definition in &document.definitions {
+            let (name, shape) = match definition {
+                Definition::SchemaDefinition(schema) => {
+                    if let Some(query) = &schema.query {
+                        index.query = query.clone();
+                    }
+                    if let Some(mutation) = &schema.mutation {
+                        index.mutation = mutation.clone();
+                    }
+                    continue;
+                }
+                Definition::TypeDefinition(TypeDefinition::Scalar(t)) => (
+                    &t.name,
+                    TypeShape {
+                        kind: "scalar",
+                        fields: BTreeMap::new(),
+                    },
+                ),
+                Definition::TypeDefinition(TypeDefinition::Object(t)) => (
+                    &t.name,
+                    TypeShape {
+                        kind: "type",
+                        fields: field_shapes(&t.fields),
+                    },
+                ),
+                Definition::TypeDefinition(TypeDefinition::Interface(t)) => (
+                    &t.name,
+                    TypeShape {
+                        kind: "interface",
+                        fields: field_shapes(&t.fields),
+                    },
+                ),
+                Definition::TypeDefinition(TypeDefinition::Union(t)) => (
+                    &t.name,
+                    TypeShape {
+                        kind: "union",
+                        fields: members(&t.types),
+                    },
+                ),
+                Definition::TypeDefinition(TypeDefinition::Enum(t)) => (
+                    &t.name,
+                    TypeShape {
+                        kind: "enum",
+                        fields: members(t.values.iter().map(|v| &v.name)),
+                    },
+                ),
+                Definition::TypeDefinition(TypeDefinition::InputObject(t)) => (
+                    &t.name,
+                    TypeShape {
+                        kind: "input",
+                        fields: t
+                            .fields
+                            .iter()
+                            .map(|f| (f.name.clone(), FieldShape::new(&f.value_type, &[])))
+                            .collect(),
+                    },
+                ),
+                Definition::TypeExtension(_) | Definition::DirectiveDefinition(_) => continue,
+            };
+            index.types.insert(name.clone(), shape);
+        }
+        Ok(index)
+    }
+
+    fn field_type(&self, type_name: &str, field: &str) -> Option<&str> {
+        Some(&self.types.get(type_name)?.fields.get(field)?.named)
+    }
+}
+
+/// Which operations use each `(type, field)` of the schema. A field of `""` means every
+/// value of an enum or member of a union the operation may receive.
+#[derive(Debug, Clone, Default)]
+pub struct Usage(BTreeMap<(String, String), BTreeSet<String>>);
+
+impl Usage {
+    fn add(&mut self, type_name: &str, field: &str, operation: &str) {
+        self.0
+            .entry((type_name.to_string(), field.to_string()))
+            .or_default()
+            .insert(operation.to_string());
+    }
+
+    fn used_by(&self, type_name: &str, field: Option<&str>) -> Vec<String> {
+        let mut ops = BTreeSet::new();
+        for ((t, f), users) in &self.0 {
+            if t == type_name && field.is_none_or(|field| f == field || f.is_empty()) {
+                ops.extend(users.iter().cloned());
+            }
+        }
+        ops.into_iter().collect()
+    }
+
+    /// Record a type an operation sends or receives. Enums and unions are used as a whole;
+    /// input objects are used field by field, recursively. Output objects only count through
+    /// the fields selected from them.
+    fn add_type(&mut self, schema: &SchemaIndex, type_name: &str, operation: &str) {
+        let Some(shape) = schema.types.get(type_name) else {
+            return;
+        };
+        match shape.kind {
+            "enum" | "union" | "scalar" => self.add(type_name, "", operation),
+            "input" => {
+                let first_visit = self
+                    .0
+                    .entry((type_name.to_string(), String::new()))
+                    .or_default()
+                    .insert(operation.to_string());
+                if !first_visit {
+                    return;
+                }
+                for (field, field_shape) in &shape.fields {
+                    self.add(type_name, field, operation);
+                    self.add_type(schema, &field_shape.named, operation);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn add_selection(
+        &mut self,
+        schema: &SchemaIndex,
+        fragments: &HashMap<&str, &q::FragmentDefinition<'_, String>>,
+        type_name: &str,
+        selection: &q::SelectionSet<'_, String>,
+        operation: &str,
+    ) -> Result<()> {
+        for item in &selection.items {
+            match item {
+                Selection::Field(field) => {
+                    if field.name == "__typename" {
+                        continue;
+                    }
+                    self.add(type_name, &field.name, operation);
+                    let inner = schema.field_type(type_name, &field.name).ok_or_else(|| {
+                        schema_error(format!(
+                            "{operation} selects unknown field {type_name}.{}",
+                            field.name
+                        ))
+                    })?;
+                    self.add_type(schema, inner, operation);
+                    self.add_selection(schema, fragments, inner, &field.selection_set, operation)?;
+                }
+                Selection::FragmentSpread(spread) => {
+                    let fragment =
+                        fragments
+                            .get(spread.fragment_name.as_str())
+                            .ok_or_else(|| {
+                                schema_error(format!("unknown fragment {}", spread.fragment_name))
+                            })?;
+                    let TypeCondition::On(on) = &fragment.type_condition;
+                    self.add_selection(schema, fragments, on, &fragment.selection_set, operation)?;
+                }
+                Selection::InlineFragment(inline) => {
+                    let on = match &inline.type_condition {
+                        Some(TypeCondition::On(on)) => on.as_str(),
+                        None => type_name,
+                    };
+                    self.add_selection(schema, fragments, on, &inline.selection_set, operation)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Work out which parts of `schema` the given documents use. Fails if a document
+    /// selects a field the schema does not have.
+    pub fn of_documents<'a>(
+        schema: &SchemaIndex,
+        documents: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Self> {
+        let mut usage = Usage::default();
+        for source in documents {
+            let document = graphql_parser::parse_query::<String>(source).map_err(schema_error)?;
+            let fragments: HashMap<_, _> = document
+                .definitions
+                .iter()
+                .filter_map(|d| match d {
+                    q::Definition::Fragment(f) => Some((f.name.as_str(), f)),
+                    _ => None,
+                })
+                .collect();
+            for definition in &document.definitions {
+                let (root, name, variables, selection) = match definition {
+                    q::Definition::Operation(q::OperationDefinition::Query(op)) => (
+                        &schema.query,
+                        &op.name,
+                        &op.variable_definitions,
+                        &op.selection_set,
+                    ),
+                    q::Definition::Operation(q::OperationDefinition::Mutation(op)) => (
+                        &schema.mutation,
+                        &op.name,
+                        &op.variable_definitions,
+                        &op.selection_set,
+                    ),
+                    _ => continue,
+                };
+                let operation = name.as_deref().unwrap_or("<anonymous>");
+                for variable in variables {
+                    usage.add_type(schema, named_type(&variable.var_type), operation);
+                }
+                usage.add_selection(schema, &fragments, root, selection, operation)?;
+            }
+        }
+        Ok(usage)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    /// Type or arguments changed, rendered as `Type` or `(args): Type` before and after.
+    Changed {
+        before: String,
+        after: String,
+    },
+}
+
+/// One difference between two schemas.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaChange {
+    pub type_name: String,
+    /// `None` when the whole type was added or removed.
+    pub field: Option<String>,
+    pub kind: ChangeKind,
+    /// Operations in the crate's documents that use the changed type or field.
+    pub used_by: Vec<String>,
+}
+
+impl SchemaChange {
+    /// Whether the change can break one of the crate's documents: something they use was
+    /// removed or changed, or an input object they send gained a non-null field.
+    pub fn is_breaking(&self) -> bool {
+        !self.used_by.is_empty()
+    }
+}
+
+impl Display for SchemaChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let target = match &self.field {
+            Some(field) => format!("{}.{}", self.type_name, field),
+            None => self.type_name.clone(),
+        };
+        match &self.kind {
+            ChangeKind::Added => write!(f, "added {target}")?,
+            ChangeKind::Removed => write!(f, "removed {target}")?,
+            ChangeKind::Changed { before, after } => {
+                write!(f, "changed {target}: {before} -> {after}")?
+            }
+        }
+        if !self.used_by.is_empty() {
+            write!(f, " (used by {})", self.used_by.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare `before` (usually the vendored schema) with `after` (usually the live one).
+/// Usage is taken from `documents`, resolved against `before`.
+pub fn diff<'a>(
+    before: &str,
+    after: &str,
+    documents: impl IntoIterator<Item = &'a str>,
+) -> Result<Vec<SchemaChange>> {
+    let before = SchemaIndex::parse(before)?;
+    let after = SchemaIndex::parse(after)?;
+    let usage = Usage::of_documents(&before, documents)?;
+
+    let mut changes = Vec::new();
+    for (name, old) in &before.types {
+        let Some(new) = after.types.get(name) else {
+            changes.push(SchemaChange {
+                type_name: name.clone(),
+                field: None,
+                kind: ChangeKind::Removed,
+                used_by: usage.used_by(name, None),
+            });
+            continue;
+        };
+        for (field, old_shape) in &old.fields {
+            let kind = match new.fields.get(field) {
+                None => ChangeKind::Removed,
+                Some(new_shape) if new_shape != old_shape => ChangeKind::Changed {
+                    before: old_shape.to_string(),
+                    after: new_shape.to_string(),
+                },
+                Some(_) => continue,
+            };
+            changes.push(SchemaChange {
+                type_name: name.clone(),
+                field: Some(field.clone()),
+                kind,
+                used_by: usage.used_by(name, Some(field)),
+            });
+        }
+        for (field, new_shape) in new
+            .fields
+            .iter()
+            .filter(|(f, _)| !old.fields.contains_key(*f))
+        {
+            // Documents that send the input object don't set the new field. Introspection
+            // carries no default values, so a non-null field is taken to be required.
+            let required = new.kind == "input" && new_shape.ty.ends_with('!');
+            changes.push(SchemaChange {
+                type_name: name.clone(),
+                field: Some(field.clone()),
+                kind: ChangeKind::Added,
+                used_by: if required {
+                    usage.used_by(name, None)
+                } else {
+                    vec![]
+                },
+            });
+        }
+    }
+    for name in after
+        .types
+        .keys()
+        .filter(|t| !before.types.contains_key(*t))
+    {
+        changes.push(SchemaChange {
+            type_name: name.clone(),
+            field: None,
+            kind: ChangeKind::Added,
+            used_by: vec![],
+        });
+    }
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn documents() -> impl Iterator<Item = &'static str> {
+        DOCUMENTS.iter().map(|(_, source)| *source)
+    }
+
+    #[test]
+    fn documents_match_vendored_schema() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/gql");
+        let mut files: Vec<_> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .filter(|f| f.ends_with(".graphql") && f != "schema.graphql")
+            .collect();
+        files.sort();
+        let listed: Vec<_> = DOCUMENTS.iter().map(|(name, _)| name.to_string()).collect();
+        assert_eq!(files, listed);
+
+        let schema = SchemaIndex::parse(VENDORED_SCHEMA).unwrap();
+        Usage::of_documents(&schema, documents()).unwrap();
+        assert!(diff(VENDORED_SCHEMA, VENDORED_SCHEMA, documents())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn reports_changes_to_used_fields() {
+        let live = VENDORED_SCHEMA
+            .replace("    adjustedCostPerHr: Float!\n", "")
+            .replace(
+                "    lowestBidPriceToResume: Float\n",
+                "    lowestBidPriceToResume: Int\n",
+            )
+            .replace("    volumeKey: String\n", "");
+        let changes = diff(VENDORED_SCHEMA, &live, documents()).unwrap();
+        let change = |field: &str| {
+            changes
+                .iter()
+                .find(|c| c.type_name == "Pod" && c.field.as_deref() == Some(field))
+                .unwrap()
+        };
+
+        let removed = change("adjustedCostPerHr");
+        assert_eq!(removed.kind, ChangeKind::Removed);
+        assert!(removed.is_breaking());
+        assert_eq!(removed.used_by, ["GetPod", "MyselfQuery"]);
+        assert_eq!(
+            change("lowestBidPriceToResume").to_string(),
+            "changed Pod.lowestBidPriceToResume: Float -> Int (used by GetPod, MyselfQuery)"
+        );
+        assert!(!change("volumeKey").is_breaking());
+    }
+
+    #[test]
+    fn required_input_fields_are_breaking() {
+        let live = VENDORED_SCHEMA.replace(
+            "input PodFindAndDeployOnDemandInput {\n",
+            "input PodFindAndDeployOnDemandInput {\n    foo: String!\n    bar: String\n",
+        );
+        let changes = diff(VENDORED_SCHEMA, &live, documents()).unwrap();
+        let change = |field: &str| {
+            changes
+                .iter()
+                .find(|c| {
+                    c.type_name == "PodFindAndDeployOnDemandInput"
+                        && c.field.as_deref() == Some(field)
+                })
+                .unwrap()
+        };
+
+        let required = change("foo");
+        assert_eq!(required.kind, ChangeKind::Added);
+        assert!(required.is_breaking());
+        assert_eq!(required.used_by, ["SpawnPodOnDemand"]);
+        assert!(!change("bar").is_breaking());
+    }
+
+    #[test]
+    fn renders_introspection_as_sdl() {
+        let data = serde_json::json!({"__schema": {
+            "queryType": {"name": "Query"},
+            "mutationType": null,
+            "subscriptionType": null,
+            "types": [
+                {"kind": "OBJECT", "name": "Query", "fields": [{
+                    "name": "pod",
+                    "args": [{"name": "id", "type": {"kind": "NON_NULL", "ofType": {"kind": "SCALAR", "name": "String"}}}],
+                    "type": {"kind": "OBJECT", "name": "Pod"},
+                }], "interfaces": []},
+                {"kind": "OBJECT", "name": "Pod", "fields": [{
+                    "name": "env", "args": [],
+                    "type": {"kind": "LIST", "ofType": {"kind": "SCALAR", "name": "String"}},
+                }], "interfaces": []},
+                {"kind": "ENUM", "name": "PodStatus", "enumValues": [{"name": "RUNNING"}]},
+                {"kind": "SCALAR", "name": "String"},
+                {"kind": "OBJECT", "name": "__Type", "fields": [], "interfaces": []},
+            ],
+        }});
+        let sdl = sdl_from_introspection(&data).unwrap();
+        assert!(sdl.contains("type Query {\n  pod(id: String!): Pod\n}"));
+        assert!(sdl.contains("type Pod {\n  env: [String]\n}"));
+        assert!(sdl.contains("enum PodStatus {\n  RUNNING\n}"));
+        assert!(!sdl.contains("__Type"));
+        SchemaIndex::parse(&sdl).unwrap();
+    }
+}
@@ -0,0 +1,244 @@
+use crate::gql::{list_teams, team_members, DateTime};
+use crate::RunpodError;
+use serde_json::Value;
+use std::fmt::Display;
+use std::str::FromStr;
+use tracing::error;
+
+/// A user's role within a team.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TeamRole {
+    Owner,
+    Admin,
+    Member,
+    Dev,
+    Billing,
+    Basic,
+}
+
+impl Display for TeamRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TeamRole::Owner => write!(f, "owner"),
+            TeamRole::Admin => write!(f, "admin"),
+            TeamRole::Member => write!(f, "member"),
+            TeamRole::Dev => write!(f, "dev"),
+            TeamRole::Billing => write!(f, "billing"),
+            TeamRole::Basic => write!(f, "basic"),
+        }
+    }
+}
+
+impl FromStr for TeamRole {
+    type Err = RunpodError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "owner" => Ok(TeamRole::Owner),
+            "admin" => Ok(TeamRole::Admin),
+            "member" => Ok(TeamRole::Member),
+            "dev" => Ok(TeamRole::Dev),
+            "billing" => Ok(TeamRole::Billing),
+            "basic" => Ok(TeamRole::Basic),
+            other => Err(RunpodError::InvalidInput(format!(
+                "unknown team role: {other}"
+            ))),
+        }
+    }
+}
+
+impl From<team_members::TeamRole> for TeamRole {
+    fn from(role: team_members::TeamRole) -> Self {
+        match role {
+            team_members::TeamRole::owner => TeamRole::Owner,
+            team_members::TeamRole::admin => TeamRole::Admin,
+            team_members::TeamRole::member => TeamRole::Member,
+            team_members::TeamRole::dev => TeamRole::Dev,
+            team_members::TeamRole::billing => TeamRole::Billing,
+            team_members::TeamRole::basic => TeamRole::Basic,
+            other => {
+                error! {"Unknown team role: {:?}", other};
+                TeamRole::Basic
+            }
+        }
+    }
+}
+
+/// The role recorded in a membership's `scopes`, which the API returns either as
+/// `{"role": "admin"}` or as the bare role name.
+fn role_from_scopes(scopes: Option<&Value>) -> Option<TeamRole> {
+    let role = match scopes? {
+        Value::Object(map) => map.get("role")?.as_str()?,
+        Value::String(role) => role.as_str(),
+        _ => return None,
+    };
+    match role.parse() {
+        Ok(role) => Some(role),
+        Err(_) => {
+            error! {"Unknown team role in scopes: {}", role};
+            None
+        }
+    }
+}
+
+#[cfg_attr(
+    feature = "tabled",
+    derive(tabled::Tabled),
+    tabled(display(Option, "crate::types::display_option", ""))
+)]
+#[derive(Debug, Clone)]
+pub struct Team {
+    pub id: String,
+    pub name: Option<String>,
+    pub owner_email: Option<String>,
+    /// The current user's role in this team.
+    pub role: Option<TeamRole>,
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub is_owner: bool,
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub available_roles: Vec<String>,
+}
+
+impl Team {
+    /// Whether `id_or_name` is this team's id or (case-insensitively) its name.
+    pub fn matches(&self, id_or_name: &str) -> bool {
+        self.id == id_or_name
+            || self
+                .name
+                .as_deref()
+                .is_some_and(|name| name.eq_ignore_ascii_case(id_or_name))
+    }
+}
+
+impl From<list_teams::TeamFields> for Team {
+    fn from(team: list_teams::TeamFields) -> Self {
+        let is_owner = team.is_owner.unwrap_or(false);
+        let role = if is_owner {
+            Some(TeamRole::Owner)
+        } else {
+            team.membership
+                .and_then(|m| role_from_scopes(m.scopes.as_ref()))
+        };
+        Self {
+            id: team.id.unwrap_or_default(),
+            name: team.name,
+            owner_email: team.owner.email,
+            role,
+            is_owner,
+            available_roles: team
+                .available_roles
+                .unwrap_or_default()
+                .into_iter()
+                .flatten()
+                .collect(),
+        }
+    }
+}
+
+#[cfg_attr(
+    feature = "tabled",
+    derive(tabled::Tabled),
+    tabled(display(Option, "crate::types::display_option", ""))
+)]
+#[derive(Debug, Clone)]
+pub struct TeamMember {
+    pub membership_id: String,
+    pub user_id: Option<String>,
+    pub email: Option<String>,
+    pub role: Option<TeamRole>,
+    pub created_at: DateTime,
+}
+
+impl From<team_members::TeamMemberFieldsMembers> for TeamMember {
+    fn from(membership: team_members::TeamMemberFieldsMembers) -> Self {
+        Self {
+            membership_id: membership.id,
+            user_id: membership.member.id,
+            email: membership.member.email,
+            role: role_from_scopes(membership.scopes.as_ref()),
+            created_at: membership.created_at,
+        }
+    }
+}
+
+/// An invitation to join a team that has not been accepted yet.
+#[cfg_attr(feature = "tabled", derive(tabled::Tabled))]
+#[derive(Debug, Clone)]
+pub struct TeamInvite {
+    pub id: String,
+    pub role: TeamRole,
+    pub created_at: DateTime,
+    pub expires_at: DateTime,
+}
+
+impl From<team_members::TeamMemberFieldsInvites> for TeamInvite {
+    fn from(invite: team_members::TeamMemberFieldsInvites) -> Self {
+        Self {
+            id: invite.id,
+            role: invite.role.into(),
+            created_at: invite.created_at,
+            expires_at: invite.expires_at,
+        }
+    }
+}
+
+/// Members and pending invites of a team.
+#[derive(Debug, Clone)]
+pub struct TeamRoster {
+    pub team_id: String,
+    pub team_name: Option<String>,
+    pub members: Vec<TeamMember>,
+    pub invites: Vec<TeamInvite>,
+}
+
+impl TeamRoster {
+    pub fn matches(&self, id_or_name: &str) -> bool {
+        self.team_id == id_or_name
+            || self
+                .team_name
+                .as_deref()
+                .is_some_and(|name| name.eq_ignore_ascii_case(id_or_name))
+    }
+}
+
+impl From<team_members::TeamMemberFields> for TeamRoster {
+    fn from(team: team_members::TeamMemberFields) -> Self {
+        Self {
+            team_id: team.id.unwrap_or_default(),
+            team_name: team.name,
+            members: team
+                .members
+                .unwrap_or_default()
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            invites: team
+                .invites
+                .unwrap_or_default()
+                .into_iter()
+                .flatten()
+                .map(Into::into)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_role_from_scopes() {
+        assert_eq!(
+            role_from_scopes(Some(&json!({"role": "admin"}))),
+            Some(TeamRole::Admin)
+        );
+        assert_eq!(
+            role_from_scopes(Some(&json!("billing"))),
+            Some(TeamRole::Billing)
+        );
+        assert_eq!(role_from_scopes(Some(&json!(["pods:read"]))), None);
+        assert_eq!(role_from_scopes(None), None);
+    }
+}
@@ -0,0 +1,535 @@
+//! An in-process fake of the Runpod GraphQL API, for tests that exercise the full client
+//! without network access or an API key. Enabled by the `testing` feature.
+//!
+//! ```no_run
+//! # async fn example() -> runpod::Result<()> {
+//! use runpod::testing::{FakeGpu, FakeRunpod};
+//! use runpod::types::SpawnPodSpec;
+//!
+//! let fake = FakeRunpod::start().await;
+//! fake.add_gpu(FakeGpu::new("NVIDIA GeForce RTX 4090", 0.69, 2));
+//! let client = fake.client();
+//!
+//! let spec = SpawnPodSpec::builder()
+//!     .name("test")
+//!     .gpu_type_id("NVIDIA GeForce RTX 4090")
+//!     .template_id("runpod-torch-v240")
+//!     .build();
+//! let id = client.spawn_pod(spec).await?;
+//! assert!(client.get_pod(&id).await?.is_some());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Supported operations: listing pods, `pod`, `gpuTypes`, both spawn mutations, `podStop`,
+//! `podTerminate`, and listing and saving templates. Anything else is answered with a
+//! GraphQL error naming the operation.
+//!
+//! New pods start as `CREATED` and turn `RUNNING` after [`FakeRunpod::set_startup_polls`]
+//! reads. Spawning more GPUs than a type has available fails with [`NO_CAPACITY_MESSAGE`],
+//! stopping or terminating an unknown pod with [`POD_NOT_FOUND_MESSAGE`], and
+//! [`FakeRunpod::rate_limit_next`] makes the next requests answer `429 Too Many Requests`.
+//! Both messages are assumed wording rather than recorded responses, so tests against the
+//! fake can't show that the real API's errors are classified correctly.
+
+use crate::config::Config;
+use crate::RunpodClient;
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::SystemTime;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+/// The API key [`FakeRunpod::client`] uses. Requests with any other key get a `401`.
+pub const FAKE_API_KEY: &str = "fake-api-key";
+
+/// The message the fake sends when no machine has enough free GPUs. The wording is the one
+/// users of the official SDKs report; it was not recorded from the API by this crate.
+pub const NO_CAPACITY_MESSAGE: &str = "There are no longer any instances available with the requested specifications. Please refresh and try again.";
+
+/// The message the fake sends for an unknown pod id. Assumed wording, not recorded from the
+/// API.
+pub const POD_NOT_FOUND_MESSAGE: &str = "Pod not found";
+
+/// A GPU type the fake offers.
+#[derive(Debug, Clone)]
+pub struct FakeGpu {
+    pub id: String,
+    pub display_name: String,
+    pub memory_in_gb: i64,
+    /// On-demand price per GPU per hour, in both secure and community cloud.
+    pub price: f64,
+    /// Spot price per GPU per hour.
+    pub spot_price: f64,
+    /// GPUs free to rent. Spawning takes from this and stopping or terminating gives back.
+    pub available: i64,
+}
+
+impl FakeGpu {
+    pub fn new(id: &str, price: f64, available: i64) -> Self {
+        Self {
+            id: id.to_string(),
+            display_name: id.to_string(),
+            memory_in_gb: 24,
+            price,
+            spot_price: (price / 2.0 * 100.0).round() / 100.0,
+            available,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FakePod {
+    id: String,
+    name: String,
+    spot: bool,
+    status: &'static str,
+    gpu_type_id: String,
+    gpu_count: i64,
+    image_name: String,
+    container_disk_in_gb: i64,
+    env: Vec<String>,
+    cost_per_hr: f64,
+    created_at: String,
+    last_started_at: String,
+    last_status_change: String,
+    /// Reads left before a `CREATED` pod turns `RUNNING`.
+    polls_until_running: u32,
+    /// Whether the pod's GPUs are taken from the pool.
+    holds_gpus: bool,
+}
+
+impl FakePod {
+    fn to_json(&self) -> Value {
+        let running = self.status == "RUNNING";
+        json!({
+            "id": self.id,
+            "name": self.name,
+            "podType": if self.spot { "INTERRUPTABLE" } else { "RESERVED" },
+            "desiredStatus": self.status,
+            "imageName": self.image_name,
+            "gpuCount": self.gpu_count,
+            "vcpuCount": 8.0 * self.gpu_count as f64,
+            "memoryInGb": 32.0 * self.gpu_count as f64,
+            "volumeInGb": 0.0,
+            "containerDiskInGb": self.container_disk_in_gb,
+            "lowestBidPriceToResume": null,
+            "adjustedCostPerHr": self.cost_per_hr,
+            "createdAt": self.created_at,
+            "lastStartedAt": self.last_started_at,
+            "lastStatusChange": self.last_status_change,
+            "dockerArgs": null,
+            "env": self.env,
+            "runtime": if running { json!({"uptimeInSeconds": 0}) } else { Value::Null },
+            "latestTelemetry": null,
+            "machine": {
+                "id": format!("machine-{}", self.id),
+                "gpuTypeId": self.gpu_type_id,
+                "location": "US",
+                "machineSystem": {"cudaVersion": "12.4", "kernelVersion": "6.5.0"},
+                "gpuType": {"id": self.gpu_type_id, "memoryInGb": 24, "cudaCores": null},
+            },
+        })
+    }
+}
+
+#[derive(Debug)]
+struct FakeState {
+    gpus: Vec<FakeGpu>,
+    pods: Vec<FakePod>,
+    templates: Vec<Value>,
+    next_id: u64,
+    startup_polls: u32,
+    rate_limited: u32,
+    operations: Vec<String>,
+}
+
+impl FakeState {
+    fn new_id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{prefix}{:06}", self.next_id)
+    }
+
+    /// Advance pods that are starting up; called on every read of pod state.
+    fn poll(&mut self) {
+        for pod in &mut self.pods {
+            if pod.status != "CREATED" {
+                continue;
+            }
+            if pod.polls_until_running > 0 {
+                pod.polls_until_running -= 1;
+                continue;
+            }
+            pod.status = "RUNNING";
+            pod.last_started_at = now();
+            pod.last_status_change = "Rented by User".into();
+        }
+    }
+
+    fn release(&mut self, index: usize) {
+        let pod = &mut self.pods[index];
+        if !pod.holds_gpus {
+            return;
+        }
+        pod.holds_gpus = false;
+        let (gpu_type_id, count) = (pod.gpu_type_id.clone(), pod.gpu_count);
+        if let Some(gpu) = self.gpus.iter_mut().find(|g| g.id == gpu_type_id) {
+            gpu.available += count;
+        }
+    }
+
+    fn find_pod(&self, id: &str) -> Option<usize> {
+        self.pods.iter().position(|p| p.id == id)
+    }
+
+    fn handle(&mut self, operation: &str, variables: &Value) -> std::result::Result<Value, String> {
+        let input = &variables["input"];
+        match operation {
+            "MyselfQuery" => {
+                self.poll();
+                let pods: Vec<_> = self.pods.iter().map(FakePod::to_json).collect();
+                Ok(json!({"myself": {"pods": pods}}))
+            }
+            "GetPod" => {
+                self.poll();
+                let id = input["podId"].as_str().unwrap_or_default();
+                let pod = self.find_pod(id).map(|i| self.pods[i].to_json());
+                Ok(json!({"pod": pod}))
+            }
+            "GpuTypes" => {
+                let ids: Option<Vec<&str>> = input["ids"]
+                    .as_array()
+                    .map(|ids| ids.iter().filter_map(Value::as_str).collect());
+                let gpus: Vec<_> = self
+                    .gpus
+                    .iter()
+                    .filter(|g| input["id"].as_str().is_none_or(|id| id == g.id))
+                    .filter(|g| ids.as_ref().is_none_or(|ids| ids.contains(&g.id.as_str())))
+                    .map(gpu_json)
+                    .collect();
+                Ok(json!({"gpuTypes": gpus}))
+            }
+            "SpawnPodOnDemand" => {
+                let pod = self.spawn(input, false)?;
+                Ok(json!({"podFindAndDeployOnDemand": pod}))
+            }
+            "BidSpot" => {
+                let pod = self.spawn(input, true)?;
+                Ok(json!({"podRentInterruptable": pod}))
+            }
+            "StopPod" => {
+                let id = input["podId"].as_str().unwrap_or_default();
+                let index = self.find_pod(id).ok_or(POD_NOT_FOUND_MESSAGE)?;
+                self.release(index);
+                let pod = &mut self.pods[index];
+                pod.status = "EXITED";
+                pod.last_status_change = "Exited by User".into();
+                Ok(json!({"podStop": {"id": pod.id, "desiredStatus": pod.status}}))
+            }
+            "TerminatePod" => {
+                let id = input["podId"].as_str().unwrap_or_default();
+                let index = self.find_pod(id).ok_or(POD_NOT_FOUND_MESSAGE)?;
+                self.release(index);
+                self.pods.remove(index);
+                Ok(json!({"podTerminate": null}))
+            }
+            "GetTemplates" => Ok(json!({"myself": {"podTemplates": self.templates}})),
+            "SaveTemplate" => {
+                let id = match input["id"].as_str() {
+                    Some(id) => id.to_string(),
+                    None => self.new_id("tpl"),
+                };
+                let template = json!({
+                    "id": id,
+                    "name": input["name"],
+                    "imageName": input["imageName"],
+                    "containerDiskInGb": input["containerDiskInGb"],
+                    "containerRegistryAuthId": input["containerRegistryAuthId"],
+                    "dockerArgs": input["dockerArgs"],
+                    "env": input["env"],
+                    "startSsh": input["startSsh"],
+                });
+                match self.templates.iter_mut().find(|t| t["id"] == id) {
+                    Some(existing) => *existing = template.clone(),
+                    None => self.templates.push(template.clone()),
+                }
+                Ok(json!({"saveTemplate": template}))
+            }
+            other => Err(format!("The fake Runpod API does not support {other}")),
+        }
+    }
+
+    fn spawn(&mut self, input: &Value, spot: bool) -> std::result::Result<Value, String> {
+        let gpu_type_id = input["gpuTypeId"].as_str().unwrap_or_default().to_string();
+        let gpu_count = input["gpuCount"].as_i64().unwrap_or(1);
+        let gpu = self
+            .gpus
+            .iter_mut()
+            .find(|g| g.id == gpu_type_id)
+            .ok_or_else(|| format!("Unknown GPU type: {gpu_type_id}"))?;
+        if gpu.available < gpu_count {
+            return Err(NO_CAPACITY_MESSAGE.to_string());
+        }
+        gpu.available -= gpu_count;
+        let price = if spot {
+            input["bidPerGpu"].as_f64().unwrap_or(gpu.spot_price)
+        } else {
+            gpu.price
+        };
+
+        let template = input["templateId"]
+            .as_str()
+            .and_then(|id| self.templates.iter().find(|t| t["id"] == id));
+        let image_name = input["imageName"]
+            .as_str()
+            .or_else(|| template.and_then(|t| t["imageName"].as_str()))
+            .unwrap_or("runpod/base:latest")
+            .to_string();
+        let env = input["env"]
+            .as_array()
+            .map(|env| {
+                env.iter()
+                    .map(|e| {
+                        format!(
+                            "{}={}",
+                            e["key"].as_str().unwrap_or_default(),
+                            e["value"].as_str().unwrap_or_default()
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let id = self.new_id("pod");
+        let created_at = now();
+        self.pods.push(FakePod {
+            id: id.clone(),
+            name: input["name"].as_str().unwrap_or_default().to_string(),
+            spot,
+            status: "CREATED",
+            gpu_type_id,
+            gpu_count,
+            image_name,
+            container_disk_in_gb: input["containerDiskInGb"].as_i64().unwrap_or(20),
+            env,
+            cost_per_hr: price * gpu_count as f64,
+            last_started_at: created_at.clone(),
+            created_at,
+            last_status_change: "Create".into(),
+            polls_until_running: self.startup_polls,
+            holds_gpus: true,
+        });
+        Ok(json!({"id": id, "machineId": format!("machine-{id}")}))
+    }
+}
+
+fn gpu_json(gpu: &FakeGpu) -> Value {
+    json!({
+        "id": gpu.id,
+        "displayName": gpu.display_name,
+        "manufacturer": "Nvidia",
+        "memoryInGb": gpu.memory_in_gb,
+        "cudaCores": null,
+        "secureCloud": true,
+        "communityCloud": true,
+        "securePrice": gpu.price,
+        "communityPrice": gpu.price,
+        "oneWeekPrice": null,
+        "oneMonthPrice": null,
+        "threeMonthPrice": null,
+        "sixMonthPrice": null,
+        "communitySpotPrice": gpu.spot_price,
+        "secureSpotPrice": gpu.spot_price,
+        "maxGpuCount": 8,
+        "maxGpuCountCommunityCloud": 8,
+        "maxGpuCountSecureCloud": 8,
+        "minPodGpuCount": 1,
+        "lowestPrice": {
+            "gpuName": gpu.display_name,
+            "gpuTypeId": gpu.id,
+            "minimumBidPrice": gpu.spot_price,
+            "uninterruptablePrice": gpu.price,
+            "stockStatus": if gpu.available > 0 { json!("High") } else { Value::Null },
+            "totalCount": gpu.available,
+        },
+    })
+}
+
+fn now() -> String {
+    humantime::format_rfc3339_seconds(SystemTime::now()).to_string()
+}
+
+/// A fake Runpod API listening on a local port. Stops when dropped.
+pub struct FakeRunpod {
+    addr: SocketAddr,
+    state: Arc<Mutex<FakeState>>,
+    server: JoinHandle<()>,
+}
+
+impl FakeRunpod {
+    /// Start the fake on a free port on `127.0.0.1`, with no GPUs, pods or templates.
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("failed to bind fake Runpod API");
+        let addr = listener
+            .local_addr()
+            .expect("fake Runpod API has no address");
+        let state = Arc::new(Mutex::new(FakeState {
+            gpus: Vec::new(),
+            pods: Vec::new(),
+            templates: Vec::new(),
+            next_id: 0,
+            startup_polls: 1,
+            rate_limited: 0,
+            operations: Vec::new(),
+        }));
+        let server = tokio::spawn(serve(listener, state.clone()));
+        Self {
+            addr,
+            state,
+            server,
+        }
+    }
+
+    /// The GraphQL endpoint, for use as `apiurl`.
+    pub fn url(&self) -> String {
+        format!("http://{}/graphql", self.addr)
+    }
+
+    /// A client pointed at the fake.
+    pub fn client(&self) -> RunpodClient {
+        RunpodClient::new(Config::new(FAKE_API_KEY, self.url()))
+    }
+
+    pub fn add_gpu(&self, gpu: FakeGpu) {
+        self.state().gpus.push(gpu);
+    }
+
+    /// How many reads of pod state show a new pod as `CREATED` before it is `RUNNING`.
+    /// Defaults to 1, so the first listing after spawning shows the pod starting up.
+    pub fn set_startup_polls(&self, polls: u32) {
+        self.state().startup_polls = polls;
+    }
+
+    /// Answer the next `count` requests with `429 Too Many Requests`.
+    pub fn rate_limit_next(&self, count: u32) {
+        self.state().rate_limited = count;
+    }
+
+    /// Exit a running spot pod the way a preemption does.
+    pub fn preempt(&self, pod_id: &str) {
+        let mut state = self.state();
+        let Some(index) = state.find_pod(pod_id) else {
+            return;
+        };
+        state.release(index);
+        let pod = &mut state.pods[index];
+        pod.status = "EXITED";
+        pod.last_status_change = "Exited by Runpod: spot instance preempted".into();
+    }
+
+    /// GPUs of `gpu_type_id` currently free.
+    pub fn available(&self, gpu_type_id: &str) -> Option<i64> {
+        self.state()
+            .gpus
+            .iter()
+            .find(|g| g.id == gpu_type_id)
+            .map(|g| g.available)
+    }
+
+    /// Names of the operations received so far, in order, including rate-limited ones.
+    pub fn operations(&self) -> Vec<String> {
+        self.state().operations.clone()
+    }
+
+    fn state(&self) -> MutexGuard<'_, FakeState> {
+        self.state.lock().expect("fake Runpod state poisoned")
+    }
+}
+
+impl Drop for FakeRunpod {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+async fn serve(listener: TcpListener, state: Arc<Mutex<FakeState>>) {
+    loop {
+        let Ok((stream, _)) = listener.accept().await else {
+            continue;
+        };
+        let state = state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, state).await {
+                warn!("Fake Runpod API connection failed: {}", e);
+            }
+        });
+    }
+}
+
+async fn handle_connection(stream: TcpStream, state: Arc<Mutex<FakeState>>) -> std::io::Result<()> {
+    let mut stream = BufReader::new(stream);
+    loop {
+        let mut request_line = String::new();
+        if stream.read_line(&mut request_line).await? == 0 {
+            return Ok(());
+        }
+        let mut content_length = 0;
+        let mut authorized = false;
+        loop {
+            let mut line = String::new();
+            stream.read_line(&mut line).await?;
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            let Some((name, value)) = line.split_once(':') else {
+                continue;
+            };
+            match name.to_ascii_lowercase().as_str() {
+                "content-length" => content_length = value.trim().parse().unwrap_or(0),
+                "authorization" => authorized = value.trim() == format!("Bearer {FAKE_API_KEY}"),
+                _ => {}
+            }
+        }
+        let mut body = vec![0; content_length];
+        stream.read_exact(&mut body).await?;
+
+        let (status, response) = respond(&state, authorized, &body);
+        let response = format!(
+            "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{response}",
+            response.len()
+        );
+        stream.get_mut().write_all(response.as_bytes()).await?;
+    }
+}
+
+fn respond(state: &Mutex<FakeState>, authorized: bool, body: &[u8]) -> (&'static str, String) {
+    let request: Value = serde_json::from_slice(body).unwrap_or_default();
+    let operation = request["operationName"].as_str().unwrap_or("unknown");
+    let mut state = state.lock().expect("fake Runpod state poisoned");
+    state.operations.push(operation.to_string());
+
+    if !authorized {
+        return (
+            "401 Unauthorized",
+            json!({"error": "Invalid API key"}).to_string(),
+        );
+    }
+    if state.rate_limited > 0 {
+        state.rate_limited -= 1;
+        return (
+            "429 Too Many Requests",
+            json!({"error": "Too many requests"}).to_string(),
+        );
+    }
+    let response = match state.handle(operation, &request["variables"]) {
+        Ok(data) => json!({"data": data}),
+        Err(message) => json!({"data": null, "errors": [{"message": message}]}),
+    };
+    ("200 OK", response.to_string())
+}
@@ -0,0 +1,94 @@
+//! How [`RunpodClient`](crate::RunpodClient) gets GraphQL requests to the API.
+//!
+//! The client serializes each query, adds its headers and hands the result to a
+//! [`Transport`]. [`ReqwestTransport`] POSTs it over HTTPS and is used unless another
+//! transport is installed with
+//! [`RunpodClient::with_transport`](crate::RunpodClient::with_transport), e.g. an
+//! in-memory fake for tests or a `reqwest` client configured for a proxy.
+
+use crate::error::Result;
+use futures::future::BoxFuture;
+use reqwest::header::HeaderMap;
+use reqwest::{Client as ReqwestClient, StatusCode, Url};
+
+/// A serialized GraphQL request, ready to send.
+#[derive(Debug, Clone)]
+pub struct TransportRequest {
+    pub url: Url,
+    /// The GraphQL operation name, e.g. `GetPod`.
+    pub operation: &'static str,
+    /// Headers set by the client, currently just `authorization`.
+    pub headers: HeaderMap,
+    /// The JSON request body: query, operation name and variables.
+    pub body: Vec<u8>,
+}
+
+/// The raw HTTP response to a [`TransportRequest`].
+#[derive(Debug, Clone)]
+pub struct TransportResponse {
+    pub status: StatusCode,
+    pub body: Vec<u8>,
+}
+
+impl TransportResponse {
+    /// A `200 OK` response with the given body.
+    pub fn ok(body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status: StatusCode::OK,
+            body: body.into(),
+        }
+    }
+}
+
+/// Sends a GraphQL request and returns the response body.
+///
+/// Transports only move bytes. Status codes, GraphQL errors and deserialization are handled
+/// by the client, so a transport should return an error only when no response was received.
+pub trait Transport: Send + Sync {
+    fn send(&self, request: TransportRequest) -> BoxFuture<'_, Result<TransportResponse>>;
+}
+
+/// The default transport: an HTTP POST with `reqwest`.
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    client: ReqwestClient,
+}
+
+impl ReqwestTransport {
+    pub fn new() -> Self {
+        let client = ReqwestClient::builder()
+            .user_agent("graphql-rust/0.10.0")
+            .build()
+            .expect("Failed to create HTTP client");
+        Self { client }
+    }
+
+    /// Use a preconfigured `reqwest` client, e.g. with client certificates or a proxy.
+    pub fn with_client(client: ReqwestClient) -> Self {
+        Self { client }
+    }
+}
+
+impl Default for ReqwestTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(&self, request: TransportRequest) -> BoxFuture<'_, Result<TransportResponse>> {
+        Box::pin(async move {
+            let response = self
+                .client
+                .post(request.url)
+                .headers(request.headers)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(request.body)
+                .send()
+                .await?;
+            let status = response.status();
+            let body = response.bytes().await?.to_vec();
+            Ok(TransportResponse { status, body })
+        })
+    }
+}
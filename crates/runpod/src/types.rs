@@ -1,6 +1,11 @@
+use crate::datetime;
 use crate::gql::*;
+use crate::savings::SavingsPlanSpec;
+use crate::RunpodError;
 use std::fmt::Display;
+use std::time::Duration;
 use tracing::error;
+use typed_builder::TypedBuilder;
 
 #[cfg_attr(
     feature = "tabled",
@@ -22,12 +27,20 @@ pub struct Pod {
     pub adjusted_cost_per_hr: f64,
     pub lowest_bid_price_to_resume: Option<f64>,
     #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub created_at: DateTime,
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub last_started_at: DateTime,
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub last_status_change: Option<String>,
+    #[cfg_attr(feature = "tabled", tabled(skip))]
     pub docker_args: Option<String>,
     #[cfg_attr(feature = "tabled", tabled(skip))]
     pub env: Vec<String>,
     #[cfg_attr(feature = "tabled", tabled(skip))]
     pub runtime: Option<PodRuntime>,
     #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub latest_telemetry: Option<PodTelemetry>,
+    #[cfg_attr(feature = "tabled", tabled(skip))]
     pub machine: PodMachineInfo,
 }
 
@@ -36,6 +49,152 @@ pub struct PodRuntime {
     pub uptime_in_seconds: Option<i64>,
 }
 
+/// Most recent telemetry sample reported for a pod.
+#[derive(Debug, Clone)]
+pub struct PodTelemetry {
+    pub state: Option<String>,
+    pub time: Option<DateTime>,
+    pub cpu_utilization: Option<f64>,
+    pub memory_utilization: Option<f64>,
+    pub average_gpu_metrics: Option<GpuTelemetry>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GpuTelemetry {
+    pub id: Option<String>,
+    pub percent_utilization: Option<f64>,
+    pub temperature_celcius: Option<f64>,
+    pub memory_utilization: Option<f64>,
+    pub power_watts: Option<f64>,
+}
+
+impl Pod {
+    /// Average GPU utilization (0-100) from the latest telemetry sample, if any.
+    pub fn gpu_utilization(&self) -> Option<f64> {
+        self.latest_telemetry
+            .as_ref()?
+            .average_gpu_metrics
+            .as_ref()?
+            .percent_utilization
+    }
+
+    pub fn uptime_in_seconds(&self) -> Option<i64> {
+        self.runtime.as_ref()?.uptime_in_seconds
+    }
+
+    /// Time since the pod was created.
+    #[cfg(feature = "chrono")]
+    pub fn age(&self) -> chrono::TimeDelta {
+        self.created_at.elapsed()
+    }
+
+    /// Time since the pod was last started.
+    #[cfg(feature = "chrono")]
+    pub fn running_for(&self) -> chrono::TimeDelta {
+        self.last_started_at.elapsed()
+    }
+}
+
+/// A point in time given either absolutely or relative to when it is resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Deadline {
+    At(DateTime),
+    After(Duration),
+}
+
+impl Deadline {
+    /// The absolute time of this deadline, counting `After` from now.
+    pub fn resolve(&self) -> DateTime {
+        match self {
+            Deadline::At(at) => at.to_owned(),
+            Deadline::After(after) => datetime::from_now(*after),
+        }
+    }
+}
+
+/// Parses a duration such as `4h` or `1day 2h`, falling back to an absolute time.
+impl std::str::FromStr for Deadline {
+    type Err = RunpodError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match humantime::parse_duration(s) {
+            Ok(after) => Ok(Deadline::After(after)),
+            Err(_) => datetime::parse_datetime(s).map(Deadline::At),
+        }
+    }
+}
+
+/// Everything needed to spawn a pod with [`RunpodClient::spawn_pod`](crate::RunpodClient::spawn_pod).
+#[derive(Debug, Clone, TypedBuilder)]
+pub struct SpawnPodSpec {
+    #[builder(setter(into))]
+    pub name: String,
+    #[builder(setter(into))]
+    pub gpu_type_id: String,
+    #[builder(default = 1)]
+    pub gpu_count: i64,
+    /// Rent an interruptable spot instance instead of an on-demand one.
+    #[builder(default = false)]
+    pub spot: bool,
+    #[builder(default, setter(strip_option))]
+    pub bid_per_gpu: Option<f64>,
+    #[builder(default, setter(strip_option))]
+    pub container_disk_in_gb: Option<i64>,
+    #[builder(setter(into))]
+    pub template_id: String,
+    /// Environment variables for the container. Values may reference secrets with
+    /// `{{ RUNPOD_SECRET_name }}`, see [`EnvironmentVariable::secret`].
+    #[builder(default)]
+    pub env: Vec<EnvironmentVariable>,
+    /// Stop the pod automatically at this time.
+    #[builder(default, setter(strip_option))]
+    pub stop_after: Option<Deadline>,
+    /// Terminate the pod automatically at this time.
+    #[builder(default, setter(strip_option))]
+    pub terminate_after: Option<Deadline>,
+    /// Credentials for pulling the image from a private registry.
+    #[builder(default, setter(strip_option))]
+    pub container_registry_auth: Option<RegistryAuthRef>,
+    /// Buy a savings plan for the pod. Only valid for on-demand pods.
+    #[builder(default, setter(strip_option))]
+    pub savings_plan: Option<SavingsPlanSpec>,
+}
+
+/// A saved container registry credential, referenced either by its id or by its name.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegistryAuthRef {
+    Id(String),
+    Name(String),
+}
+
+/// Everything needed to create or update a template with
+/// [`RunpodClient::save_template`](crate::RunpodClient::save_template).
+#[derive(Debug, Clone, TypedBuilder)]
+pub struct SaveTemplateSpec {
+    /// Id of the template to update. Leave unset to create a new one.
+    #[builder(default, setter(strip_option, into))]
+    pub id: Option<String>,
+    #[builder(setter(into))]
+    pub name: String,
+    #[builder(setter(into))]
+    pub image_name: String,
+    #[builder(default = 20)]
+    pub container_disk_in_gb: i64,
+    #[builder(default, setter(strip_option))]
+    pub volume_in_gb: Option<i64>,
+    #[builder(default, setter(strip_option, into))]
+    pub volume_mount_path: Option<String>,
+    /// Exposed ports, e.g. `8888/http,22/tcp`.
+    #[builder(default, setter(strip_option, into))]
+    pub ports: Option<String>,
+    #[builder(default)]
+    pub env: Vec<EnvironmentVariable>,
+    #[builder(default, setter(strip_option, into))]
+    pub docker_args: Option<String>,
+    #[builder(default, setter(strip_option))]
+    pub container_registry_auth: Option<RegistryAuthRef>,
+}
+
 #[derive(Debug, Clone)]
 pub struct PodMachineInfo {
     pub id: String,
@@ -44,7 +203,7 @@ pub struct PodMachineInfo {
     pub machine_system: MachineSystem,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PodType {
     Interruptable,
     Reserved,
@@ -93,6 +252,7 @@ impl From<get_pod::PodType> for PodType {
     }
 }
 
+#[allow(clippy::useless_conversion)]
 impl From<myself_query::MyselfQueryMyselfPods> for Pod {
     fn from(pod: myself_query::MyselfQueryMyselfPods) -> Self {
         Self {
@@ -108,7 +268,11 @@ impl From<myself_query::MyselfQueryMyselfPods> for Pod {
             container_disk_in_gb: pod.container_disk_in_gb,
             adjusted_cost_per_hr: pod.adjusted_cost_per_hr,
             lowest_bid_price_to_resume: pod.lowest_bid_price_to_resume,
+            created_at: pod.created_at,
+            last_started_at: pod.last_started_at,
+            last_status_change: pod.last_status_change,
             runtime: pod.runtime.map(Into::into),
+            latest_telemetry: pod.latest_telemetry.map(Into::into),
             machine: pod.machine.into(),
             docker_args: pod.docker_args,
             env: pod
@@ -124,6 +288,7 @@ impl From<myself_query::MyselfQueryMyselfPods> for Pod {
     }
 }
 
+#[allow(clippy::filter_map_identity)]
 impl From<get_pod::GetPodPod> for Pod {
     fn from(pod: get_pod::GetPodPod) -> Self {
         Self {
@@ -140,7 +305,11 @@ impl From<get_pod::GetPodPod> for Pod {
             container_disk_in_gb: pod.container_disk_in_gb,
             adjusted_cost_per_hr: pod.adjusted_cost_per_hr,
             lowest_bid_price_to_resume: pod.lowest_bid_price_to_resume,
+            created_at: pod.created_at,
+            last_started_at: pod.last_started_at,
+            last_status_change: pod.last_status_change,
             runtime: pod.runtime.map(Into::into),
+            latest_telemetry: pod.latest_telemetry.map(Into::into),
             machine: pod.machine.into(),
             env: pod
                 .env
@@ -158,6 +327,30 @@ impl From<get_pod::GetPodPodRuntime> for PodRuntime {
     }
 }
 
+impl From<get_pod::GetPodPodLatestTelemetry> for PodTelemetry {
+    fn from(telemetry: get_pod::GetPodPodLatestTelemetry) -> Self {
+        Self {
+            state: telemetry.state,
+            time: telemetry.time,
+            cpu_utilization: telemetry.cpu_utilization,
+            memory_utilization: telemetry.memory_utilization,
+            average_gpu_metrics: telemetry.average_gpu_metrics.map(Into::into),
+        }
+    }
+}
+
+impl From<get_pod::GetPodPodLatestTelemetryAverageGpuMetrics> for GpuTelemetry {
+    fn from(gpu: get_pod::GetPodPodLatestTelemetryAverageGpuMetrics) -> Self {
+        Self {
+            id: gpu.id,
+            percent_utilization: gpu.percent_utilization,
+            temperature_celcius: gpu.temperature_celcius,
+            memory_utilization: gpu.memory_utilization,
+            power_watts: gpu.power_watts,
+        }
+    }
+}
+
 impl From<get_pod::GetPodPodMachine> for PodMachineInfo {
     fn from(machine: get_pod::GetPodPodMachine) -> Self {
         Self {
@@ -196,6 +389,30 @@ impl From<myself_query::MyselfQueryMyselfPodsRuntime> for PodRuntime {
     }
 }
 
+impl From<myself_query::MyselfQueryMyselfPodsLatestTelemetry> for PodTelemetry {
+    fn from(telemetry: myself_query::MyselfQueryMyselfPodsLatestTelemetry) -> Self {
+        Self {
+            state: telemetry.state,
+            time: telemetry.time,
+            cpu_utilization: telemetry.cpu_utilization,
+            memory_utilization: telemetry.memory_utilization,
+            average_gpu_metrics: telemetry.average_gpu_metrics.map(Into::into),
+        }
+    }
+}
+
+impl From<myself_query::MyselfQueryMyselfPodsLatestTelemetryAverageGpuMetrics> for GpuTelemetry {
+    fn from(gpu: myself_query::MyselfQueryMyselfPodsLatestTelemetryAverageGpuMetrics) -> Self {
+        Self {
+            id: gpu.id,
+            percent_utilization: gpu.percent_utilization,
+            temperature_celcius: gpu.temperature_celcius,
+            memory_utilization: gpu.memory_utilization,
+            power_watts: gpu.power_watts,
+        }
+    }
+}
+
 impl From<myself_query::MyselfQueryMyselfPodsMachine> for PodMachineInfo {
     fn from(machine: myself_query::MyselfQueryMyselfPodsMachine) -> Self {
         Self {
@@ -207,7 +424,7 @@ impl From<myself_query::MyselfQueryMyselfPodsMachine> for PodMachineInfo {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PodStatus {
     Created,
     Running,
@@ -300,6 +517,53 @@ impl From<myself_query::MyselfQueryMyselfPodsMachineGpuType> for GpuType {
     }
 }
 
+#[cfg_attr(
+    feature = "tabled",
+    derive(tabled::Tabled),
+    tabled(display(Option, "display_option", ""))
+)]
+#[derive(Debug, Clone)]
+pub struct AccountSummary {
+    pub current_spend_per_hr: Option<f64>,
+    pub client_balance: Option<f64>,
+    pub spend_limit: Option<i64>,
+    pub under_balance: Option<bool>,
+    /// Balance below which Runpod sends a low-credit alert.
+    pub credit_alert_threshold: Option<f64>,
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub spend_details: Option<SpendDetails>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SpendDetails {
+    pub local_storage_per_hour: Option<f64>,
+    pub network_storage_per_hour: Option<f64>,
+    pub gpu_compute_per_hour: Option<f64>,
+}
+
+impl From<account_summary_query::AccountSummaryQueryMyself> for AccountSummary {
+    fn from(myself: account_summary_query::AccountSummaryQueryMyself) -> Self {
+        Self {
+            current_spend_per_hr: myself.current_spend_per_hr,
+            client_balance: myself.client_balance,
+            spend_limit: myself.spend_limit,
+            under_balance: myself.under_balance,
+            credit_alert_threshold: myself.credit_alert_threshold,
+            spend_details: myself.spend_details.map(Into::into),
+        }
+    }
+}
+
+impl From<account_summary_query::AccountSummaryQueryMyselfSpendDetails> for SpendDetails {
+    fn from(details: account_summary_query::AccountSummaryQueryMyselfSpendDetails) -> Self {
+        Self {
+            local_storage_per_hour: details.local_storage_per_hour,
+            network_storage_per_hour: details.network_storage_per_hour,
+            gpu_compute_per_hour: details.gpu_compute_per_hour,
+        }
+    }
+}
+
 #[cfg_attr(feature = "tabled", derive(tabled::Tabled))]
 #[derive(Debug, Clone)]
 pub enum Compliance {
@@ -336,7 +600,8 @@ impl From<gpu_types::Compliance> for Compliance {
     }
 }
 
-fn display_option<T>(opt: &Option<T>, default: &str) -> String
+#[cfg(feature = "tabled")]
+pub(crate) fn display_option<T>(opt: &Option<T>, default: &str) -> String
 where
     T: ToString,
 {
@@ -365,13 +630,9 @@ pub struct GpuOffer {
     pub community_cloud: Option<bool>,
     pub secure_price: Option<f64>,
     pub community_price: Option<f64>,
-    #[cfg_attr(feature = "tabled", tabled(skip))]
     pub one_month_price: Option<f64>,
-    #[cfg_attr(feature = "tabled", tabled(skip))]
     pub three_month_price: Option<f64>,
-    #[cfg_attr(feature = "tabled", tabled(skip))]
     pub six_month_price: Option<f64>,
-    #[cfg_attr(feature = "tabled", tabled(skip))]
     pub one_week_price: Option<f64>,
     pub community_spot_price: Option<f64>,
     pub secure_spot_price: Option<f64>,
@@ -474,12 +735,215 @@ impl From<gpu_types::GpuTypesGpuTypesLowestPrice> for LowestPrice {
     derive(tabled::Tabled),
     tabled(display(Option, "display_option", ""))
 )]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct EnvironmentVariable {
     pub key: String,
     pub value: String,
 }
 
+impl EnvironmentVariable {
+    pub fn new(key: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+
+    /// An environment variable that Runpod fills in from the named secret when the pod starts.
+    pub fn secret(key: impl Into<String>, secret_name: &str) -> Self {
+        Self::new(key, format!("{{{{ RUNPOD_SECRET_{secret_name} }}}}"))
+    }
+
+    /// Names of the secrets referenced as `{{ RUNPOD_SECRET_name }}` in the value.
+    pub fn secret_refs(&self) -> Vec<&str> {
+        let mut refs = Vec::new();
+        let mut rest = self.value.as_str();
+        while let Some(start) = rest.find("{{") {
+            let Some(len) = rest[start..].find("}}") else {
+                break;
+            };
+            let inner = rest[start + 2..start + len].trim();
+            if let Some(name) = inner.strip_prefix("RUNPOD_SECRET_") {
+                refs.push(name);
+            }
+            rest = &rest[start + len + 2..];
+        }
+        refs
+    }
+}
+
+impl From<EnvironmentVariable> for spawn_pod_on_demand::EnvironmentVariableInput {
+    fn from(env: EnvironmentVariable) -> Self {
+        Self {
+            key: env.key,
+            value: env.value,
+        }
+    }
+}
+
+impl From<EnvironmentVariable> for bid_spot::EnvironmentVariableInput {
+    fn from(env: EnvironmentVariable) -> Self {
+        Self {
+            key: env.key,
+            value: env.value,
+        }
+    }
+}
+
+impl From<EnvironmentVariable> for save_template::EnvironmentVariableInput {
+    fn from(env: EnvironmentVariable) -> Self {
+        Self {
+            key: env.key,
+            value: env.value,
+        }
+    }
+}
+
+/// An API key on the account. The key itself is never returned by the API.
+#[cfg_attr(
+    feature = "tabled",
+    derive(tabled::Tabled),
+    tabled(display(Option, "display_option", ""))
+)]
+#[derive(Debug, Clone)]
+pub struct ApiKey {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub permissions: String,
+    pub created_at: DateTime,
+    #[cfg_attr(feature = "tabled", tabled(display = "display_last_used"))]
+    pub last_used: Option<DateTime>,
+}
+
+#[cfg(feature = "tabled")]
+fn display_last_used(last_used: &Option<DateTime>) -> String {
+    display_option(last_used, "never")
+}
+
+impl ApiKey {
+    /// When the key was last used, or when it was created if it has never been used.
+    pub fn last_activity(&self) -> &DateTime {
+        self.last_used.as_ref().unwrap_or(&self.created_at)
+    }
+
+    /// Time since the key was last used (or created, if never used).
+    #[cfg(feature = "chrono")]
+    pub fn unused_for(&self) -> chrono::TimeDelta {
+        self.last_activity().elapsed()
+    }
+
+    /// Whether the key has gone unused for at least `days` days.
+    #[cfg(feature = "chrono")]
+    pub fn is_stale(&self, days: i64) -> bool {
+        self.unused_for() >= chrono::TimeDelta::days(days)
+    }
+}
+
+impl From<list_api_keys::ListApiKeysMyselfApiKeys> for ApiKey {
+    fn from(key: list_api_keys::ListApiKeysMyselfApiKeys) -> Self {
+        Self {
+            id: key.id,
+            name: key.name,
+            permissions: key.permissions,
+            created_at: key.created_at,
+            last_used: key.last_used,
+        }
+    }
+}
+
+/// Saved credentials for a private container registry. The username and password are
+/// write-only and never returned by the API.
+#[cfg_attr(feature = "tabled", derive(tabled::Tabled))]
+#[derive(Debug, Clone)]
+pub struct RegistryAuth {
+    pub id: String,
+    pub name: String,
+}
+
+impl RegistryAuth {
+    /// Credentials the API returned without an id can't be referenced, so they are skipped.
+    fn from_parts(id: Option<String>, name: Option<String>) -> Option<Self> {
+        Some(Self {
+            id: id?,
+            name: name.unwrap_or_default(),
+        })
+    }
+}
+
+impl From<list_registry_auths::ListRegistryAuthsMyselfContainerRegistryCreds>
+    for Option<RegistryAuth>
+{
+    fn from(auth: list_registry_auths::ListRegistryAuthsMyselfContainerRegistryCreds) -> Self {
+        RegistryAuth::from_parts(auth.id, auth.name)
+    }
+}
+
+impl From<save_registry_auth::SaveRegistryAuthSaveRegistryAuth> for Option<RegistryAuth> {
+    fn from(auth: save_registry_auth::SaveRegistryAuthSaveRegistryAuth) -> Self {
+        RegistryAuth::from_parts(auth.id, auth.name)
+    }
+}
+
+#[cfg_attr(
+    feature = "tabled",
+    derive(tabled::Tabled),
+    tabled(display(Option, "display_option", ""))
+)]
+#[derive(Debug, Clone)]
+pub struct Secret {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: DateTime,
+    pub updated_at: Option<DateTime>,
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub last_retrieved_at: Option<DateTime>,
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub last_updated_at: Option<DateTime>,
+}
+
+impl From<create_secret::CreateSecretSecretCreate> for Secret {
+    fn from(secret: create_secret::CreateSecretSecretCreate) -> Self {
+        Self {
+            id: secret.id,
+            name: secret.name,
+            description: secret.description,
+            created_at: secret.created_at,
+            updated_at: secret.updated_at,
+            last_retrieved_at: secret.last_retrieved_at,
+            last_updated_at: secret.last_updated_at,
+        }
+    }
+}
+
+impl From<update_secret::UpdateSecretSecretUpdate> for Secret {
+    fn from(secret: update_secret::UpdateSecretSecretUpdate) -> Self {
+        Self {
+            id: secret.id,
+            name: secret.name,
+            description: secret.description,
+            created_at: secret.created_at,
+            updated_at: secret.updated_at,
+            last_retrieved_at: secret.last_retrieved_at,
+            last_updated_at: secret.last_updated_at,
+        }
+    }
+}
+
+impl From<list_secrets::ListSecretsMyselfSecrets> for Secret {
+    fn from(secret: list_secrets::ListSecretsMyselfSecrets) -> Self {
+        Self {
+            id: secret.id,
+            name: secret.name,
+            description: secret.description,
+            created_at: secret.created_at,
+            updated_at: secret.updated_at,
+            last_retrieved_at: secret.last_retrieved_at,
+            last_updated_at: secret.last_updated_at,
+        }
+    }
+}
+
 impl From<get_templates::GetTemplatesMyselfPodTemplatesEnv> for EnvironmentVariable {
     fn from(env: get_templates::GetTemplatesMyselfPodTemplatesEnv) -> Self {
         Self {
@@ -498,14 +962,11 @@ impl From<get_templates::GetTemplatesMyselfPodTemplatesEnv> for EnvironmentVaria
 //     }
 // }
 
-// impl From<save_template::SaveTemplateSaveTemplateEnv> for EnvironmentVariable {
-//     fn from(env: save_template::SaveTemplateSaveTemplateEnv) -> Self {
-//         Self {
-//             key: env.key,
-//             value: env.value,
-//         }
-//     }
-// }
+impl From<save_template::SaveTemplateSaveTemplateEnv> for Option<EnvironmentVariable> {
+    fn from(env: save_template::SaveTemplateSaveTemplateEnv) -> Self {
+        Some(EnvironmentVariable::new(env.key?, env.value?))
+    }
+}
 
 // "advancedStart": false,
 // "containerDiskInGb": 987,
@@ -531,6 +992,7 @@ impl From<get_templates::GetTemplatesMyselfPodTemplatesEnv> for EnvironmentVaria
 // "config": {},
 // "category": "abc123"
 
+#[allow(dead_code, unused_variables)]
 fn display_environment_variable(a: ()) -> String {
     "".to_string()
 }
@@ -547,24 +1009,24 @@ fn display_environment_variable(a: ()) -> String {
 pub struct Template {
     // advanced_start: Option<bool>,
     // container_disk_in_gb: i64,
-    // container_registry_auth_id: Option<String>,
-    docker_args: Option<String>,
+    pub container_registry_auth_id: Option<String>,
+    pub docker_args: Option<String>,
     // earned: f64,
     #[cfg_attr(feature = "tabled", tabled(skip))]
-    env: Option<Vec<EnvironmentVariable>>,
-    id: Option<String>,
-    image_name: Option<String>,
+    pub env: Option<Vec<EnvironmentVariable>>,
+    pub id: Option<String>,
+    pub image_name: Option<String>,
     // is_public: bool,
     // is_runpod: bool,
     // is_serverless: bool,
     // bound_endpoint_id: Option<String>,
-    // name: String,
+    pub name: Option<String>,
     // ports: Option<String>,
     // readme: Option<String>,
     // runtime_in_min: i64,
     // start_jupyter: bool,
     // start_script: Option<String>,
-    start_ssh: Option<bool>,
+    pub start_ssh: Option<bool>,
     // volume_in_gb: f64,
     // volume_mount_path: Option<String>,
     // config: serde_json::Value,
@@ -576,7 +1038,7 @@ impl From<get_templates::GetTemplatesMyselfPodTemplates> for Template {
         Self {
             // advanced_start: template.advanced_start,
             // container_disk_in_gb: template.container_disk_in_gb,
-            // container_registry_auth_id: template.container_registry_auth_id,
+            container_registry_auth_id: template.container_registry_auth_id,
             docker_args: template.docker_args,
             // earned: template.earned,
             // // env: template.env.map(|v| v.into_iter().map(Into::into).collect()),
@@ -586,7 +1048,7 @@ impl From<get_templates::GetTemplatesMyselfPodTemplates> for Template {
             // is_runpod: template.is_runpod,
             // is_serverless: template.is_serverless,
             // bound_endpoint_id: template.bound_endpoint_id,
-            // name: template.name,
+            name: template.name,
             // ports: template.ports,
             // readme: template.readme,
             // runtime_in_min: template.runtime_in_min,
@@ -606,3 +1068,126 @@ impl From<get_templates::GetTemplatesMyselfPodTemplates> for Template {
         }
     }
 }
+
+impl From<save_template::SaveTemplateSaveTemplate> for Template {
+    fn from(template: save_template::SaveTemplateSaveTemplate) -> Self {
+        Self {
+            container_registry_auth_id: template.container_registry_auth_id,
+            docker_args: template.docker_args,
+            id: template.id,
+            image_name: template.image_name,
+            name: template.name,
+            start_ssh: template.start_ssh,
+            env: template.env.map(|env_vec| {
+                env_vec
+                    .into_iter()
+                    .filter_map(|opt_env| opt_env.and_then(Into::into))
+                    .collect()
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+impl Pod {
+    /// A running single-GPU pod with no telemetry, for tests.
+    pub(crate) fn sample(id: &str, name: &str) -> Self {
+        Pod {
+            id: id.into(),
+            name: name.into(),
+            pod_type: None,
+            desired_status: PodStatus::Running,
+            image_name: "img".into(),
+            gpu_count: 1,
+            vcpu_count: 8.0,
+            memory_in_gb: 32.0,
+            volume_in_gb: None,
+            container_disk_in_gb: 20,
+            adjusted_cost_per_hr: 0.5,
+            lowest_bid_price_to_resume: None,
+            created_at: datetime::parse_datetime("2026-01-01T00:00:00Z").unwrap(),
+            last_started_at: datetime::parse_datetime("2026-01-01T00:00:00Z").unwrap(),
+            last_status_change: None,
+            docker_args: None,
+            env: vec![],
+            runtime: None,
+            latest_telemetry: None,
+            machine: PodMachineInfo {
+                id: "m".into(),
+                gpu_type: None,
+                location: "x".into(),
+                machine_system: MachineSystem {
+                    cuda_version: "12".into(),
+                    kernel_version: "6".into(),
+                },
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn flags_stale_api_keys() {
+        let now = chrono::Utc::now();
+        let key = |created_days: i64, used_days: Option<i64>| ApiKey {
+            id: None,
+            name: None,
+            permissions: "READ".into(),
+            created_at: (now - chrono::TimeDelta::days(created_days)).into(),
+            last_used: used_days.map(|d| (now - chrono::TimeDelta::days(d)).into()),
+        };
+        assert!(key(200, Some(100)).is_stale(90));
+        assert!(!key(200, Some(10)).is_stale(90));
+        assert!(key(120, None).is_stale(90));
+        assert!(!key(5, None).is_stale(90));
+    }
+
+    #[test]
+    fn parses_deadlines() {
+        assert_eq!(
+            "4h".parse::<Deadline>().unwrap(),
+            Deadline::After(Duration::from_secs(4 * 3600))
+        );
+        assert_eq!(
+            "1day 2h".parse::<Deadline>().unwrap(),
+            Deadline::After(Duration::from_secs(26 * 3600))
+        );
+        let at = datetime::parse_datetime("2026-11-01T12:00:00Z").unwrap();
+        assert_eq!(
+            "2026-11-01T12:00:00Z".parse::<Deadline>().unwrap(),
+            Deadline::At(at)
+        );
+        assert!("2026-11-01".parse::<Deadline>().is_ok());
+        assert!("garbage".parse::<Deadline>().is_err());
+        assert!("2026-13-40".parse::<Deadline>().is_err());
+    }
+
+    #[test]
+    fn resolves_deadlines() {
+        let at = || datetime::parse_datetime("2026-11-01T12:00:00Z").unwrap();
+        assert_eq!(Deadline::At(at()).resolve(), at());
+
+        let after = Duration::from_secs(3600);
+        let earliest = datetime::from_now(after);
+        let resolved = Deadline::After(after).resolve();
+        assert!(earliest <= resolved && resolved <= datetime::from_now(after));
+        assert!(datetime::from_now(Duration::ZERO) < resolved);
+    }
+
+    #[test]
+    fn finds_secret_refs() {
+        let env = EnvironmentVariable::new(
+            "DSN",
+            "postgres://{{ RUNPOD_SECRET_db_user }}:{{RUNPOD_SECRET_db_pass}}@{{ host }}",
+        );
+        assert_eq!(env.secret_refs(), vec!["db_user", "db_pass"]);
+        assert_eq!(
+            EnvironmentVariable::secret("HF_TOKEN", "hf").secret_refs(),
+            vec!["hf"]
+        );
+    }
+}
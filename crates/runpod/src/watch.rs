@@ -0,0 +1,215 @@
+use crate::types::{Pod, PodStatus, PodType};
+use std::collections::HashMap;
+
+/// A change between two successive pod listings.
+#[derive(Debug, Clone)]
+pub enum PodEvent {
+    /// A pod appeared. Every pod is reported as added on the first poll.
+    Added(Pod),
+    /// A pod is no longer listed. Carries the last state it was seen in.
+    Removed(Pod),
+    /// The pod's desired status changed, e.g. `Created` to `Running`.
+    StatusChanged { pod: Pod, from: PodStatus },
+    /// A spot pod stopped running and its status message says the platform stopped it.
+    /// Reported instead of [`PodEvent::StatusChanged`]; stops by the user are not.
+    Preempted { pod: Pod, from: PodStatus },
+    /// The API reported a new `lastStatusChange` message for the pod.
+    StatusMessageChanged { pod: Pod, from: Option<String> },
+    /// The pod's hourly cost changed.
+    CostChanged { pod: Pod, from: f64 },
+    /// Listing pods failed. The watch carries on and retries at the next interval.
+    PollFailed(String),
+}
+
+impl PodEvent {
+    /// The pod the event is about, in its latest known state.
+    pub fn pod(&self) -> Option<&Pod> {
+        match self {
+            PodEvent::Added(pod)
+            | PodEvent::Removed(pod)
+            | PodEvent::StatusChanged { pod, .. }
+            | PodEvent::Preempted { pod, .. }
+            | PodEvent::StatusMessageChanged { pod, .. }
+            | PodEvent::CostChanged { pod, .. } => Some(pod),
+            PodEvent::PollFailed(_) => None,
+        }
+    }
+}
+
+/// Turns successive pod listings into [`PodEvent`]s.
+#[derive(Debug, Default)]
+pub struct PodWatcher {
+    pods: HashMap<String, Pod>,
+}
+
+impl PodWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compare `pods` with the previous listing and remember it for the next call.
+    pub fn diff(&mut self, pods: Vec<Pod>) -> Vec<PodEvent> {
+        let mut events = Vec::new();
+        let mut previous = std::mem::take(&mut self.pods);
+
+        for pod in pods {
+            match previous.remove(&pod.id) {
+                None => events.push(PodEvent::Added(pod.clone())),
+                Some(old) => events.extend(changes(&old, &pod)),
+            }
+            self.pods.insert(pod.id.clone(), pod);
+        }
+
+        let mut removed: Vec<Pod> = previous.into_values().collect();
+        removed.sort_by(|a, b| a.id.cmp(&b.id));
+        events.extend(removed.into_iter().map(PodEvent::Removed));
+        events
+    }
+}
+
+fn is_spot(pod: &Pod) -> bool {
+    matches!(pod.pod_type, Some(PodType::Interruptable | PodType::Bid))
+}
+
+/// Whether the pod's `lastStatusChange` blames the platform rather than the user, e.g.
+/// `Exited by Runpod` rather than `Exited by User`. The API doesn't document these
+/// messages, so anything unrecognised is not counted as a preemption.
+fn was_preempted(pod: &Pod) -> bool {
+    let Some(message) = &pod.last_status_change else {
+        return false;
+    };
+    let message = message.to_ascii_lowercase();
+    !message.contains("by user")
+        && (message.contains("preempt")
+            || message.contains("interrupt")
+            || message.starts_with("exited by runpod"))
+}
+
+fn changes(old: &Pod, new: &Pod) -> Vec<PodEvent> {
+    let mut events = Vec::new();
+    if old.desired_status != new.desired_status {
+        let from = old.desired_status.clone();
+        if is_spot(new)
+            && from == PodStatus::Running
+            && new.desired_status == PodStatus::Exited
+            && was_preempted(new)
+        {
+            events.push(PodEvent::Preempted {
+                pod: new.clone(),
+                from,
+            });
+        } else {
+            events.push(PodEvent::StatusChanged {
+                pod: new.clone(),
+                from,
+            });
+        }
+    }
+    if old.last_status_change != new.last_status_change && new.last_status_change.is_some() {
+        events.push(PodEvent::StatusMessageChanged {
+            pod: new.clone(),
+            from: old.last_status_change.clone(),
+        });
+    }
+    if (old.adjusted_cost_per_hr - new.adjusted_cost_per_hr).abs() > f64::EPSILON {
+        events.push(PodEvent::CostChanged {
+            pod: new.clone(),
+            from: old.adjusted_cost_per_hr,
+        });
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pod(id: &str, status: PodStatus) -> Pod {
+        Pod {
+            desired_status: status,
+            ..Pod::sample(id, id)
+        }
+    }
+
+    #[test]
+    fn reports_added_changed_and_removed() {
+        let mut watcher = PodWatcher::new();
+        let events = watcher.diff(vec![
+            pod("a", PodStatus::Created),
+            pod("b", PodStatus::Running),
+        ]);
+        assert!(matches!(
+            events.as_slice(),
+            [PodEvent::Added(_), PodEvent::Added(_)]
+        ));
+
+        let mut a = pod("a", PodStatus::Running);
+        a.adjusted_cost_per_hr = 0.7;
+        let events = watcher.diff(vec![a]);
+        assert!(matches!(
+            events.as_slice(),
+            [
+                PodEvent::StatusChanged {
+                    from: PodStatus::Created,
+                    ..
+                },
+                PodEvent::CostChanged { from, .. },
+                PodEvent::Removed(removed),
+            ] if *from == 0.5 && removed.id == "b"
+        ));
+
+        assert_eq!(watcher.diff(vec![pod("a", PodStatus::Running)]).len(), 1);
+        assert!(watcher.diff(vec![pod("a", PodStatus::Running)]).is_empty());
+    }
+
+    #[test]
+    fn reports_spot_preemption() {
+        let spot = |status| Pod {
+            pod_type: Some(PodType::Interruptable),
+            last_status_change: Some("Exited by Runpod: preempted".into()),
+            ..pod("s", status)
+        };
+        let mut watcher = PodWatcher::new();
+        watcher.diff(vec![spot(PodStatus::Running)]);
+        let events = watcher.diff(vec![spot(PodStatus::Exited)]);
+        assert!(matches!(
+            events.as_slice(),
+            [PodEvent::Preempted {
+                from: PodStatus::Running,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn user_stop_of_spot_pod_is_not_preemption() {
+        let spot = |status, message: &str| Pod {
+            pod_type: Some(PodType::Bid),
+            last_status_change: Some(message.into()),
+            ..pod("s", status)
+        };
+        let mut watcher = PodWatcher::new();
+        watcher.diff(vec![spot(PodStatus::Running, "Rented by User")]);
+        let events = watcher.diff(vec![spot(PodStatus::Exited, "Exited by User")]);
+        assert!(matches!(
+            events.as_slice(),
+            [
+                PodEvent::StatusChanged {
+                    from: PodStatus::Running,
+                    ..
+                },
+                PodEvent::StatusMessageChanged { .. },
+            ]
+        ));
+
+        // No message to go by: report a plain status change.
+        let mut watcher = PodWatcher::new();
+        watcher.diff(vec![pod("s", PodStatus::Running)]);
+        let mut exited = pod("s", PodStatus::Exited);
+        exited.pod_type = Some(PodType::Interruptable);
+        assert!(matches!(
+            watcher.diff(vec![exited]).as_slice(),
+            [PodEvent::StatusChanged { .. }]
+        ));
+    }
+}
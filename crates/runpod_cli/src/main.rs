@@ -1,21 +1,72 @@
 use clap::Parser;
+use futures::TryStreamExt;
+use runpod::audit::AuditLogFilter;
+use runpod::budget::{BudgetGuard, BudgetLimits};
+use runpod::config::Config;
+use runpod::exporter::Exporter;
+use runpod::host::{total_by_machine, EarningsRange};
+use runpod::notify::{Notifier, NotifyConfig};
+use runpod::reaper::{ReapAction, Reaper, ReaperConfig};
+use runpod::savings::{SavingsPlanQuote, SavingsPlanSpec};
+use runpod::schema::{self, DOCUMENTS, VENDORED_SCHEMA};
+use runpod::types::{EnvironmentVariable, RegistryAuthRef, SaveTemplateSpec, SpawnPodSpec};
 use runpod::RunpodClient;
 use std::error::Error;
+use std::io::{IsTerminal, Read, Write};
 use tabled::Table;
 use tracing::error;
 
 mod opts;
-use opts::{Cli, Commands, GpuCommands, PodCommands, TemplateCommands};
+use opts::{
+    ApikeyCommands, BillingCommands, Cli, Commands, GpuCommands, HostCommands, OutputFormat,
+    PodCommands, RegistryCommands, SaveTemplateArgs, SavingsCommands, SchemaCommands,
+    SecretCommands, TeamCommands, TemplateCommands,
+};
+
+/// Read a value from stdin, prompting first if stdin is a terminal. One trailing newline
+/// is stripped.
+fn read_stdin_value(prompt: &str) -> std::io::Result<String> {
+    if std::io::stdin().is_terminal() {
+        eprintln!("{prompt}, then press Ctrl-D:");
+    }
+    let mut value = String::new();
+    std::io::stdin().read_to_string(&mut value)?;
+    if value.ends_with('\n') {
+        value.pop();
+        if value.ends_with('\r') {
+            value.pop();
+        }
+    }
+    Ok(value)
+}
 
 #[tokio::main]
+#[allow(
+    clippy::to_string_in_format_args,
+    clippy::collapsible_else_if,
+    clippy::manual_retain
+)]
 async fn main() -> Result<(), Box<dyn Error>> {
     tracing_subscriber::fmt::init();
 
     let opts = Cli::parse();
-    let client = RunpodClient::from_config()?;
+    let mut config = Config::try_from_env()?;
+    if let Some(team) = &opts.team {
+        config = match config.for_team(team) {
+            Ok(config) => config,
+            Err(e) => {
+                error!("Failed to use team {}: {}", team, e);
+                std::process::exit(1);
+            }
+        };
+    }
+    let mut client = RunpodClient::new(config);
+    if opts.log_bodies {
+        client = client.with_body_logging(true);
+    }
 
     match opts.command {
-        Commands::Pod { command } => match command {
+        Commands::Pod { command } => match *command {
             PodCommands::List {} => match client.list_pods().await {
                 Ok(pods) => {
                     println!("{}", Table::new(pods).to_string());
@@ -43,6 +94,71 @@ async fn main() -> Result<(), Box<dyn Error>> {
                     std::process::exit(1);
                 }
             },
+            PodCommands::Reap {
+                idle,
+                gpu_threshold,
+                terminate,
+                allow,
+                deny,
+                dry_run,
+                daemon,
+                assume_idle_since_start,
+                interval,
+            } => {
+                // A single pass can only guess idle time from uptime. Stopping on that
+                // guess can be undone, terminating cannot.
+                if !daemon && terminate && !assume_idle_since_start {
+                    error!("--terminate without --daemon requires --assume-idle-since-start");
+                    std::process::exit(1);
+                }
+                let config = ReaperConfig::builder()
+                    .idle_threshold(idle)
+                    .gpu_utilization_threshold(gpu_threshold)
+                    .action(if terminate {
+                        ReapAction::Terminate
+                    } else {
+                        ReapAction::Stop
+                    })
+                    .allow(allow)
+                    .deny(deny)
+                    .dry_run(dry_run)
+                    .assume_idle_since_start(assume_idle_since_start || !daemon)
+                    .build();
+                let mut reaper = Reaper::new(client, config);
+                if daemon {
+                    reaper.run(interval).await?;
+                } else {
+                    match reaper.run_once().await {
+                        Ok(outcomes) if outcomes.is_empty() => {
+                            println!("No idle pods found");
+                        }
+                        Ok(outcomes) => {
+                            for o in outcomes {
+                                let verb = if o.applied {
+                                    "reaped"
+                                } else if dry_run {
+                                    "would reap"
+                                } else {
+                                    "failed to reap"
+                                };
+                                println!(
+                                    "{} ({}): {} ({}), idle {}, ${:.3}/hr",
+                                    o.pod_name,
+                                    o.pod_id,
+                                    verb,
+                                    o.action,
+                                    humantime::format_duration(o.idle_for),
+                                    o.adjusted_cost_per_hr
+                                );
+                            }
+                        }
+                        Err(e) => {
+                            error!("Failed to reap pods: {}", e);
+                            std::process::exit(1);
+                        }
+                    }
+                }
+            }
             PodCommands::Get { id } => match client.get_pod(&id).await {
                 Ok(Some(pod)) => {
                     println!("Pod: {pod:#?}");
@@ -64,16 +180,77 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 bid,
                 disk,
                 template,
+                env,
+                env_file,
+                secret,
+                stop_after,
+                terminate_after,
+                registry_auth,
+                savings_plan,
+                accept_unverified_savings_plan,
+                max_spend_per_hr,
+                min_runway_hours,
             } => {
                 if spot && bid.is_none() {
                     error!("Must specify --bid when using --spot");
                     std::process::exit(1);
                 }
 
-                match client
-                    .spawn_pod(name, gpu, count, spot, bid, disk, template)
-                    .await
-                {
+                let mut pod_env = Vec::new();
+                if let Some(path) = env_file {
+                    for item in dotenvy::from_path_iter(&path)? {
+                        let (key, value) = item?;
+                        pod_env.push(EnvironmentVariable::new(key, value));
+                    }
+                }
+                pod_env.extend(env);
+                pod_env.extend(secret);
+
+                let savings_plan = match savings_plan {
+                    Some(length) => {
+                        let offer = client
+                            .list_gpus(None)
+                            .await?
+                            .into_iter()
+                            .find(|offer| offer.id == gpu);
+                        let Some(quote) =
+                            offer.and_then(|offer| SavingsPlanQuote::new(&offer, count, length))
+                        else {
+                            error!("No {} savings plan price for GPU type {}", length, gpu);
+                            std::process::exit(1);
+                        };
+                        println!(
+                            "Buying {} savings plan: about ${:.2} upfront (estimate), ${:.3}/hr",
+                            length, quote.upfront_cost, quote.plan_cost_per_hr
+                        );
+                        Some(SavingsPlanSpec {
+                            accept_unverified: accept_unverified_savings_plan,
+                            ..quote.spec()
+                        })
+                    }
+                    None => None,
+                };
+
+                let limits = BudgetLimits {
+                    max_spend_per_hr,
+                    min_runway_hours,
+                };
+                let spec = SpawnPodSpec {
+                    name,
+                    gpu_type_id: gpu,
+                    gpu_count: count,
+                    spot,
+                    bid_per_gpu: bid,
+                    container_disk_in_gb: disk,
+                    template_id: template,
+                    env: pod_env,
+                    stop_after,
+                    terminate_after,
+                    container_registry_auth: registry_auth.map(RegistryAuthRef::Name),
+                    savings_plan,
+                };
+                let guard = BudgetGuard::new(client, limits);
+                match guard.spawn_pod(spec).await {
                     Ok(pod) => {
                         println!("Successfully spawned pod:");
                         println!("{}", Table::new(vec![pod]).to_string());
@@ -128,12 +305,432 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 }
             },
         },
+        Commands::Account {} => match client.account_summary().await {
+            Ok(summary) => {
+                println!("{}", Table::new(vec![summary]));
+            }
+            Err(e) => {
+                error!("Failed to get account summary: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Commands::Billing { command } => match command {
+            BillingCommands::Report {
+                granularity,
+                since,
+                group_by,
+                format,
+            } => match client.billing_report(granularity).await {
+                Ok(report) => {
+                    let rows = report.rows(group_by, since.as_ref());
+                    match format {
+                        OutputFormat::Table => println!("{}", Table::new(rows)),
+                        OutputFormat::Csv => {
+                            let mut writer = csv::Writer::from_writer(std::io::stdout());
+                            for row in rows {
+                                writer.serialize(row)?;
+                            }
+                            writer.flush()?;
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to get billing report: {}", e);
+                    std::process::exit(1);
+                }
+            },
+        },
+        Commands::Audit {
+            since,
+            until,
+            resource_type,
+            resource_id,
+        } => {
+            let filter = AuditLogFilter {
+                since,
+                until,
+                resource_type,
+                resource_id,
+            };
+            let logs = client.audit_logs(filter);
+            futures::pin_mut!(logs);
+            let mut stdout = std::io::stdout().lock();
+            loop {
+                match logs.try_next().await {
+                    Ok(Some(log)) => {
+                        serde_json::to_writer(&mut stdout, &log)?;
+                        writeln!(stdout)?;
+                    }
+                    Ok(None) => break,
+                    Err(e) => {
+                        stdout.flush()?;
+                        error!("Failed to fetch audit log: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            stdout.flush()?;
+        }
+        Commands::Exporter { listen } => {
+            if let Err(e) = Exporter::new(client).serve(listen).await {
+                error!("Metrics exporter failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Notify { rules, once } => {
+            let config = match NotifyConfig::load(&rules) {
+                Ok(config) => config,
+                Err(e) => {
+                    error!("Failed to load rules from {}: {}", rules.display(), e);
+                    std::process::exit(1);
+                }
+            };
+            let mut notifier = Notifier::new(client, config);
+            if once {
+                match notifier.run_once().await {
+                    Ok(notifications) => {
+                        for n in notifications {
+                            println!("{}: {}", n.rule, n.message);
+                        }
+                    }
+                    Err(e) => {
+                        error!("Failed to check notification rules: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            } else {
+                notifier.run().await?;
+            }
+        }
+        Commands::Schema { command } => {
+            let live = match client.fetch_schema().await {
+                Ok(live) => live,
+                Err(e) => {
+                    error!("Failed to fetch schema: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            match command {
+                SchemaCommands::Fetch { output: Some(path) } => std::fs::write(path, live)?,
+                SchemaCommands::Fetch { output: None } => print!("{live}"),
+                SchemaCommands::Diff { schema, all } => {
+                    let vendored = match schema {
+                        Some(path) => std::fs::read_to_string(path)?,
+                        None => VENDORED_SCHEMA.to_string(),
+                    };
+                    let documents = DOCUMENTS.iter().map(|(_, source)| *source);
+                    let changes = match schema::diff(&vendored, &live, documents) {
+                        Ok(changes) => changes,
+                        Err(e) => {
+                            error!("Failed to compare schemas: {}", e);
+                            std::process::exit(1);
+                        }
+                    };
+                    for change in changes.iter().filter(|c| all || !c.used_by.is_empty()) {
+                        println!("{change}");
+                    }
+                    if changes.iter().any(|c| c.is_breaking()) {
+                        std::process::exit(1);
+                    }
+                }
+            }
+        }
+        Commands::Secret { command } => match command {
+            SecretCommands::List {} => match client.list_secrets().await {
+                Ok(secrets) => {
+                    println!("{}", Table::new(secrets));
+                }
+                Err(e) => {
+                    error!("Failed to list secrets: {}", e);
+                    std::process::exit(1);
+                }
+            },
+            SecretCommands::Set { name, description } => {
+                let value = read_stdin_value(&format!("Enter the value for {name}"))?;
+                if value.is_empty() {
+                    error!("Refusing to set an empty secret");
+                    std::process::exit(1);
+                }
+                match client
+                    .set_secret(&name, &value, description.as_deref())
+                    .await
+                {
+                    Ok(_) => {
+                        println!("Secret {} saved", name);
+                    }
+                    Err(e) => {
+                        error!("Failed to save secret: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            SecretCommands::Rm { name } => {
+                let secrets = client.list_secrets().await?;
+                let Some(secret) = secrets.into_iter().find(|s| s.name == name) else {
+                    println!("Secret {name} not found");
+                    std::process::exit(1);
+                };
+                match client.delete_secret(&secret.id).await {
+                    Ok(_) => {
+                        println!("Secret {} deleted", name);
+                    }
+                    Err(e) => {
+                        error!("Failed to delete secret: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+        },
+        Commands::Savings { command } => match command {
+            SavingsCommands::List {} => match client.list_savings_plans().await {
+                Ok(plans) => {
+                    println!("{}", Table::new(plans));
+                }
+                Err(e) => {
+                    error!("Failed to list savings plans: {}", e);
+                    std::process::exit(1);
+                }
+            },
+            SavingsCommands::Compare { gpu, count } => match client.list_gpus(None).await {
+                Ok(gpus) => {
+                    let Some(offer) = gpus.into_iter().find(|offer| offer.id == gpu) else {
+                        println!("GPU type {gpu} not found");
+                        std::process::exit(1);
+                    };
+                    let quotes = SavingsPlanQuote::all(&offer, count);
+                    if quotes.is_empty() {
+                        println!("No savings plans available for {gpu}");
+                    } else {
+                        println!("{}", Table::new(quotes));
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to get GPUs: {}", e);
+                    std::process::exit(1);
+                }
+            },
+        },
+        Commands::Host { command } => match command {
+            HostCommands::Machines {} => match client.list_machines().await {
+                Ok(machines) => {
+                    println!("{}", Table::new(machines));
+                }
+                Err(e) => {
+                    error!("Failed to list machines: {}", e);
+                    std::process::exit(1);
+                }
+            },
+            HostCommands::Summary {} => match client.machines_summary().await {
+                Ok(summaries) => {
+                    println!("{}", Table::new(summaries));
+                }
+                Err(e) => {
+                    error!("Failed to get machine summary: {}", e);
+                    std::process::exit(1);
+                }
+            },
+            HostCommands::Earnings {
+                from,
+                to,
+                by_machine,
+            } => {
+                let range = from
+                    .zip(to)
+                    .map(|(start, end)| EarningsRange::between(&start, &end));
+                match client.machine_earnings(range).await {
+                    Ok(earnings) => {
+                        let total: f64 = earnings.iter().filter_map(|e| e.total).sum();
+                        if by_machine {
+                            for (machine, amount) in total_by_machine(&earnings) {
+                                println!("{machine}: ${amount:.2}");
+                            }
+                        } else {
+                            println!("{}", Table::new(earnings));
+                        }
+                        println!("Total: ${total:.2}");
+                    }
+                    Err(e) => {
+                        error!("Failed to get machine earnings: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            HostCommands::Maintenance {} => match client.maintenance_schedule().await {
+                Ok(windows) if windows.is_empty() => {
+                    println!("No upcoming maintenance");
+                }
+                Ok(windows) => {
+                    println!("{}", Table::new(windows));
+                }
+                Err(e) => {
+                    error!("Failed to get maintenance schedule: {}", e);
+                    std::process::exit(1);
+                }
+            },
+        },
+        Commands::Team { command } => match command {
+            TeamCommands::List {} => match client.list_teams().await {
+                Ok(teams) => {
+                    println!("{}", Table::new(teams));
+                }
+                Err(e) => {
+                    error!("Failed to list teams: {}", e);
+                    std::process::exit(1);
+                }
+            },
+            TeamCommands::Members { team } => {
+                let Some(team) = team.or_else(|| opts.team.clone()) else {
+                    error!("Specify a team, or pass --team");
+                    std::process::exit(1);
+                };
+                match client.team_members(&team).await {
+                    Ok(roster) => {
+                        println!("{}", Table::new(roster.members));
+                        if !roster.invites.is_empty() {
+                            println!("Pending invites:");
+                            println!("{}", Table::new(roster.invites));
+                        }
+                    }
+                    Err(e) => {
+                        error!("Failed to list team members: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+        },
+        Commands::Apikey { command } => match command {
+            ApikeyCommands::List { unused_days, stale } => match client.list_api_keys().await {
+                Ok(keys) => {
+                    let (flagged, fresh): (Vec<_>, Vec<_>) =
+                        keys.into_iter().partition(|key| key.is_stale(unused_days));
+                    if !stale {
+                        println!("{}", Table::new(&fresh));
+                    }
+                    if flagged.is_empty() {
+                        println!("No keys unused for {unused_days} days or more");
+                    } else {
+                        println!("Unused for {unused_days} days or more:");
+                        println!("{}", Table::new(&flagged));
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to list API keys: {}", e);
+                    std::process::exit(1);
+                }
+            },
+        },
+        Commands::Registry { command } => match command {
+            RegistryCommands::List {} => match client.list_registry_auths().await {
+                Ok(auths) => {
+                    println!("{}", Table::new(auths));
+                }
+                Err(e) => {
+                    error!("Failed to list registry credentials: {}", e);
+                    std::process::exit(1);
+                }
+            },
+            RegistryCommands::Add { name, username } => {
+                let password = read_stdin_value(&format!("Enter the password for {username}"))?;
+                if password.is_empty() {
+                    error!("Refusing to save an empty password");
+                    std::process::exit(1);
+                }
+                match client.save_registry_auth(&name, &username, &password).await {
+                    Ok(auth) => {
+                        println!("Registry credentials {} saved ({})", auth.name, auth.id);
+                    }
+                    Err(e) => {
+                        error!("Failed to save registry credentials: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            RegistryCommands::Rm { name } => {
+                let id = match client
+                    .resolve_registry_auth(&RegistryAuthRef::Name(name.clone()))
+                    .await
+                {
+                    Ok(id) => id,
+                    Err(e) => {
+                        error!("Failed to find registry credentials: {}", e);
+                        std::process::exit(1);
+                    }
+                };
+                match client.delete_registry_auth(&id).await {
+                    Ok(_) => {
+                        println!("Registry credentials {} deleted", name);
+                    }
+                    Err(e) => {
+                        error!("Failed to delete registry credentials: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+        },
         Commands::Template { command } => match command {
             TemplateCommands::List {} => {
                 let templates = client.get_templates().await?;
                 let table = Table::new(templates);
                 println!("{table}");
             }
+            TemplateCommands::SaveTemplate(args) => {
+                let SaveTemplateArgs {
+                    id,
+                    name,
+                    image_name,
+                    container_disk_in_gb,
+                    volume_in_gb,
+                    volume_mount_path,
+                    ports,
+                    env,
+                    docker_args,
+                    container_registry_auth_id,
+                    registry_auth,
+                    // Templates don't store GPU, vCPU or memory sizes; SaveTemplateInput has no
+                    // fields for them, so these are accepted but not sent.
+                    gpu_count: _,
+                    vcpu_count: _,
+                    memory_in_gb: _,
+                } = *args;
+                let env = match env.as_deref().map(opts::parse_env_list).transpose() {
+                    Ok(env) => env.unwrap_or_default(),
+                    Err(e) => {
+                        error!("Invalid --env: {}", e);
+                        std::process::exit(1);
+                    }
+                };
+                // The API takes whole gigabytes.
+                let volume_in_gb = match volume_in_gb {
+                    Some(gb) if gb.fract() != 0.0 => {
+                        error!("--volume-in-gb must be a whole number of GB, got {}", gb);
+                        std::process::exit(1);
+                    }
+                    gb => gb.map(|gb| gb as i64),
+                };
+                let spec = SaveTemplateSpec {
+                    id,
+                    name,
+                    image_name,
+                    container_disk_in_gb,
+                    volume_in_gb,
+                    volume_mount_path,
+                    ports,
+                    env,
+                    docker_args,
+                    container_registry_auth: registry_auth
+                        .map(RegistryAuthRef::Name)
+                        .or(container_registry_auth_id.map(RegistryAuthRef::Id)),
+                };
+                match client.save_template(spec).await {
+                    Ok(template) => {
+                        println!("{}", Table::new(vec![template]));
+                    }
+                    Err(e) => {
+                        error!("Failed to save template: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
             _ => unimplemented!(),
         },
     }
@@ -1,4 +1,11 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use runpod::billing::{BillingGranularity, BillingGroupBy};
+use runpod::datetime::{parse_datetime, DateTime};
+use runpod::savings::SavingsPlanLength;
+use runpod::types::{Deadline, EnvironmentVariable};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
 // use runpod::types::SaveTemplateInput;
 
 #[derive(Parser)]
@@ -11,6 +18,14 @@ pub struct Cli {
     /// API key for Runpod.ai (can also be set via RUNPOD_API_KEY environment variable)
     #[arg(global = true, short, long, env = "Runpod_API_KEY")]
     pub api_key: Option<String>,
+
+    /// Log full, unredacted API request and response bodies (with RUST_LOG=debug)
+    #[arg(global = true, long)]
+    pub log_bodies: bool,
+
+    /// Use the API key configured for this team under [teams] in ~/.runpod/config.toml
+    #[arg(global = true, long, env = "RUNPOD_TEAM")]
+    pub team: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -18,13 +33,89 @@ pub enum Commands {
     /// Manage pods
     Pod {
         #[command(subcommand)]
-        command: PodCommands,
+        command: Box<PodCommands>,
     },
     /// Manage GPUs
     Gpu {
         #[command(subcommand)]
         command: GpuCommands,
     },
+    /// Show account balance and spend
+    Account {},
+    /// Billing history and cost reports
+    Billing {
+        #[command(subcommand)]
+        command: BillingCommands,
+    },
+    /// Print audit log entries as newline-delimited JSON. The API only records these
+    /// for support impersonation sessions
+    Audit {
+        /// Only entries at or after this time (e.g. 2026-09-01)
+        #[arg(long, value_parser = parse_datetime)]
+        since: Option<DateTime>,
+
+        /// Only entries before this time
+        #[arg(long, value_parser = parse_datetime)]
+        until: Option<DateTime>,
+
+        /// Only entries about this kind of resource (e.g. pod)
+        #[arg(long)]
+        resource_type: Option<String>,
+
+        /// Only entries about this resource
+        #[arg(long)]
+        resource_id: Option<String>,
+    },
+    /// Serve pod and account state as Prometheus metrics on /metrics
+    Exporter {
+        /// Address to listen on; a bare `:port` listens on all interfaces
+        #[arg(long, default_value = ":9400", value_parser = parse_listen)]
+        listen: SocketAddr,
+    },
+    /// Send webhook or command notifications for pod events and low balance
+    Notify {
+        /// TOML file with the notification rules
+        rules: PathBuf,
+
+        /// Poll once and exit instead of running until interrupted
+        #[arg(long)]
+        once: bool,
+    },
+    /// Fetch the live GraphQL schema or compare it with the vendored one
+    Schema {
+        #[command(subcommand)]
+        command: SchemaCommands,
+    },
+    /// Manage secrets
+    Secret {
+        #[command(subcommand)]
+        command: SecretCommands,
+    },
+    /// Savings plan prices and active plans
+    Savings {
+        #[command(subcommand)]
+        command: SavingsCommands,
+    },
+    /// Machines hosted on Runpod by this account
+    Host {
+        #[command(subcommand)]
+        command: HostCommands,
+    },
+    /// Teams and team members
+    Team {
+        #[command(subcommand)]
+        command: TeamCommands,
+    },
+    /// Inspect API keys
+    Apikey {
+        #[command(subcommand)]
+        command: ApikeyCommands,
+    },
+    /// Manage private container registry credentials
+    Registry {
+        #[command(subcommand)]
+        command: RegistryCommands,
+    },
     /// List all templates
     Template {
         /// List all templates
@@ -72,6 +163,53 @@ pub enum PodCommands {
         /// Template ID
         #[arg(long)]
         template: String,
+
+        /// Environment variable for the container (repeatable)
+        #[arg(long, value_name = "KEY=VALUE", value_parser = parse_env)]
+        env: Vec<EnvironmentVariable>,
+
+        /// Read environment variables from a dotenv file
+        #[arg(long)]
+        env_file: Option<PathBuf>,
+
+        /// Set an environment variable from a Runpod secret (repeatable)
+        #[arg(long, value_name = "KEY=SECRET_NAME", value_parser = parse_secret_env)]
+        secret: Vec<EnvironmentVariable>,
+
+        /// Stop the pod after this long (e.g. "4h") or at this time (RFC 3339)
+        #[arg(long)]
+        stop_after: Option<Deadline>,
+
+        /// Terminate the pod after this long (e.g. "24h") or at this time (RFC 3339)
+        #[arg(long)]
+        terminate_after: Option<Deadline>,
+
+        /// Name of saved registry credentials for pulling a private image
+        #[arg(long, value_name = "NAME")]
+        registry_auth: Option<String>,
+
+        /// Buy a savings plan of this length for the pod (1w, 1m, 3m or 6m; on-demand only).
+        /// Needs --accept-unverified-savings-plan
+        #[arg(
+            long,
+            conflicts_with = "spot",
+            requires = "accept_unverified_savings_plan"
+        )]
+        savings_plan: Option<SavingsPlanLength>,
+
+        /// Buy the savings plan even though the plan length sent to the API has not been
+        /// verified against it. The API decides the amount charged; the upfront cost shown is
+        /// an estimate
+        #[arg(long)]
+        accept_unverified_savings_plan: bool,
+
+        /// Refuse to spawn if total spend would exceed this many dollars per hour
+        #[arg(long)]
+        max_spend_per_hr: Option<f64>,
+
+        /// Refuse to spawn if the balance would last fewer than this many hours
+        #[arg(long)]
+        min_runway_hours: Option<f64>,
     },
     /// Stop a pod
     Stop {
@@ -83,70 +221,78 @@ pub enum PodCommands {
         /// Pod ID to terminate
         id: String,
     },
-}
+    /// Stop or terminate pods whose GPUs have been idle for too long
+    Reap {
+        /// How long a pod must be idle before it is reaped (e.g. "2h", "30m")
+        #[arg(long, value_parser = humantime::parse_duration)]
+        idle: Duration,
 
-#[derive(Subcommand)]
-pub enum TemplateCommands {
-    /// List all templates
-    List {},
+        /// GPU utilization percentage at or below which a pod counts as idle
+        #[arg(long, default_value = "0")]
+        gpu_threshold: f64,
 
-    /// Save or update a template
-    SaveTemplate {
-        /// Template ID (optional for new templates)
+        /// Terminate idle pods instead of stopping them
         #[arg(long)]
-        id: Option<String>,
+        terminate: bool,
 
-        /// Template name
+        /// Only reap pods whose name matches one of these patterns (supports * and ?)
         #[arg(long)]
-        name: String,
+        allow: Vec<String>,
 
-        /// Docker image name
+        /// Never reap pods whose name matches one of these patterns (supports * and ?)
         #[arg(long)]
-        image_name: String,
+        deny: Vec<String>,
 
-        /// Container disk size in GB
+        /// Report what would be reaped without stopping anything
         #[arg(long)]
-        #[arg(long, default_value = "20")]
-        container_disk_in_gb: i64,
+        dry_run: bool,
 
-        /// Volume size in GB
+        /// Keep running, checking pods every --interval
         #[arg(long)]
-        #[arg(long, default_value = "60.0")]
-        volume_in_gb: Option<f64>,
+        daemon: bool,
 
-        /// Volume mount path
+        /// Count a pod that is idle when first seen as idle for its whole uptime.
+        /// Implied for single runs that stop pods; required for single runs with --terminate
         #[arg(long)]
-        volume_mount_path: Option<String>,
+        assume_idle_since_start: bool,
 
-        /// Container ports (e.g. "8080/tcp,8081/udp")
-        #[arg(long)]
-        ports: Option<String>,
+        /// Time between checks in daemon mode
+        #[arg(long, default_value = "5m", value_parser = humantime::parse_duration)]
+        interval: Duration,
+    },
+}
 
-        /// Environment variables (e.g. "KEY1=value1,KEY2=value2")
-        #[arg(long)]
-        env: Option<String>,
+fn split_key_value(s: &str) -> Result<(&str, &str), String> {
+    s.split_once('=')
+        .filter(|(key, _)| !key.is_empty())
+        .ok_or_else(|| format!("expected KEY=VALUE, got {s:?}"))
+}
 
-        /// Number of GPUs
-        #[arg(long, default_value = "1")]
-        gpu_count: Option<i64>,
+fn parse_env(s: &str) -> Result<EnvironmentVariable, String> {
+    let (key, value) = split_key_value(s)?;
+    Ok(EnvironmentVariable::new(key, value))
+}
 
-        /// Number of vCPUs
-        #[arg(long, default_value = "4.0")]
-        vcpu_count: Option<f64>,
+/// Parse a comma-separated `KEY1=value1,KEY2=value2` list.
+pub fn parse_env_list(s: &str) -> Result<Vec<EnvironmentVariable>, String> {
+    s.split(',')
+        .filter(|pair| !pair.is_empty())
+        .map(parse_env)
+        .collect()
+}
 
-        /// Memory in GB
-        #[arg(long)]
-        #[arg(long, default_value = "16.0")]
-        memory_in_gb: Option<f64>,
+fn parse_secret_env(s: &str) -> Result<EnvironmentVariable, String> {
+    let (key, secret) = split_key_value(s)?;
+    Ok(EnvironmentVariable::secret(key, secret))
+}
 
-        /// Docker arguments
-        #[arg(long)]
-        docker_args: Option<String>,
+#[derive(Subcommand)]
+pub enum TemplateCommands {
+    /// List all templates
+    List {},
 
-        /// Container registry auth ID
-        #[arg(long)]
-        container_registry_auth_id: Option<String>,
-    },
+    /// Save or update a template
+    SaveTemplate(Box<SaveTemplateArgs>),
 
     /// Remove a template
     RemoveTemplate {
@@ -155,6 +301,73 @@ pub enum TemplateCommands {
     },
 }
 
+#[derive(Args)]
+pub struct SaveTemplateArgs {
+    /// Template ID (optional for new templates)
+    #[arg(long)]
+    pub id: Option<String>,
+
+    /// Template name
+    #[arg(long)]
+    pub name: String,
+
+    /// Docker image name
+    #[arg(long)]
+    pub image_name: String,
+
+    /// Container disk size in GB
+    #[arg(long)]
+    #[arg(long, default_value = "20")]
+    pub container_disk_in_gb: i64,
+
+    /// Volume size in GB
+    #[arg(long)]
+    #[arg(long, default_value = "60.0")]
+    pub volume_in_gb: Option<f64>,
+
+    /// Volume mount path
+    #[arg(long)]
+    pub volume_mount_path: Option<String>,
+
+    /// Container ports (e.g. "8080/tcp,8081/udp")
+    #[arg(long)]
+    pub ports: Option<String>,
+
+    /// Environment variables (e.g. "KEY1=value1,KEY2=value2")
+    #[arg(long)]
+    pub env: Option<String>,
+
+    /// Number of GPUs
+    #[arg(long, default_value = "1")]
+    pub gpu_count: Option<i64>,
+
+    /// Number of vCPUs
+    #[arg(long, default_value = "4.0")]
+    pub vcpu_count: Option<f64>,
+
+    /// Memory in GB
+    #[arg(long)]
+    #[arg(long, default_value = "16.0")]
+    pub memory_in_gb: Option<f64>,
+
+    /// Docker arguments
+    #[arg(long)]
+    pub docker_args: Option<String>,
+
+    /// Container registry auth ID
+    #[arg(long)]
+    pub container_registry_auth_id: Option<String>,
+
+    /// Name of saved registry credentials for pulling a private image, instead of
+    /// --container-registry-auth-id
+    #[arg(
+        long,
+        value_name = "NAME",
+        conflicts_with = "container_registry_auth_id"
+    )]
+    pub registry_auth: Option<String>,
+}
+
 #[derive(Subcommand)]
 pub enum GpuCommands {
     /// List available GPUs
@@ -172,3 +385,166 @@ pub enum GpuCommands {
         vram: Option<i64>,
     },
 }
+
+#[derive(Subcommand)]
+pub enum SecretCommands {
+    /// List secrets (names and metadata only)
+    List {},
+    /// Create or update a secret, reading the value from stdin
+    Set {
+        /// Secret name
+        name: String,
+
+        /// Secret description
+        #[arg(long)]
+        description: Option<String>,
+    },
+    /// Delete a secret
+    Rm {
+        /// Secret name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SavingsCommands {
+    /// List savings plans bought on the account
+    List {},
+    /// Compare savings plan prices with on-demand pricing for a GPU type
+    Compare {
+        /// GPU type ID
+        #[arg(short, long)]
+        gpu: String,
+
+        /// Number of GPUs
+        #[arg(short, long, default_value = "1")]
+        count: i64,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum HostCommands {
+    /// List hosted machines
+    Machines {},
+    /// Show how much of each hosted machine is rented and what it earns per hour
+    Summary {},
+    /// Show daily earnings of hosted machines
+    Earnings {
+        /// First day to include (YYYY-MM-DD); requires --to
+        #[arg(long, requires = "to", value_parser = parse_datetime)]
+        from: Option<DateTime>,
+
+        /// Last day to include (YYYY-MM-DD)
+        #[arg(long, requires = "from", value_parser = parse_datetime)]
+        to: Option<DateTime>,
+
+        /// Show totals per machine instead of daily rows
+        #[arg(long)]
+        by_machine: bool,
+    },
+    /// List upcoming maintenance windows
+    Maintenance {},
+}
+
+fn parse_listen(s: &str) -> Result<SocketAddr, String> {
+    let addr = match s.strip_prefix(':') {
+        Some(port) => format!("0.0.0.0:{port}"),
+        None => s.to_string(),
+    };
+    addr.parse()
+        .map_err(|e| format!("invalid listen address {s:?}: {e}"))
+}
+
+#[derive(Subcommand)]
+pub enum TeamCommands {
+    /// List teams you belong to
+    List {},
+    /// List the members and pending invites of a team
+    Members {
+        /// Team id or name (defaults to --team)
+        team: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ApikeyCommands {
+    /// List API keys, flagging keys that have not been used recently
+    List {
+        /// Flag keys unused for at least this many days
+        #[arg(long, default_value = "90")]
+        unused_days: i64,
+
+        /// Only show flagged keys
+        #[arg(long)]
+        stale: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RegistryCommands {
+    /// List saved registry credentials
+    List {},
+    /// Save registry credentials, reading the password from stdin
+    Add {
+        /// Name to save the credentials under
+        name: String,
+
+        /// Registry username
+        #[arg(long)]
+        username: String,
+    },
+    /// Delete saved registry credentials
+    Rm {
+        /// Credentials name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SchemaCommands {
+    /// Introspect the live API and print its schema as SDL
+    Fetch {
+        /// Write the schema to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Compare the live schema with the vendored one, failing if fields our queries use were
+    /// removed or changed, or inputs they send gained a required field
+    Diff {
+        /// Compare against this SDL file instead of the schema built into this binary
+        #[arg(long)]
+        schema: Option<PathBuf>,
+
+        /// Also list changes to types and fields no query uses
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BillingCommands {
+    /// Report costs over time
+    Report {
+        /// hourly, daily, weekly or monthly
+        #[arg(long, default_value = "daily")]
+        granularity: BillingGranularity,
+
+        /// Only include periods starting on or after this date (e.g. 2026-09-01)
+        #[arg(long, value_parser = parse_datetime)]
+        since: Option<DateTime>,
+
+        /// gpu (per GPU type), category (GPU/CPU/serverless/storage) or charge (pod/disk/API/serverless)
+        #[arg(long, default_value = "category")]
+        group_by: BillingGroupBy,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Csv,
+}
@@ -0,0 +1,653 @@
+//! Python bindings for [`runpod::RunpodClient`], built as the `runpod_rs` extension module.
+//!
+//! Client methods return awaitables. Calls run on a tokio runtime owned by the module, and
+//! results are frozen dataclass-like objects with a `to_dict()` method:
+//!
+//! ```python
+//! import asyncio
+//! import runpod_rs
+//!
+//! async def main():
+//!     client = runpod_rs.RunpodClient()  # reads ~/.runpod/config.toml
+//!     for gpu in await client.list_gpus():
+//!         print(gpu.id, gpu.secure_price, gpu.community_price)
+//!     pod_id = await client.spawn_pod("exp", "NVIDIA RTX 4090", "runpod-torch", env={"FOO": "1"})
+//!     print(await client.get_pod(pod_id))
+//!
+//! asyncio.run(main())
+//! ```
+//!
+//! Build the wheel with `maturin build --release` in this directory. The crate is kept out
+//! of the root workspace so that building the rest doesn't need Python; run its tests with
+//! `cargo test` here.
+
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3::IntoPyObjectExt;
+use runpod::config::Config;
+use runpod::types::{EnvironmentVariable, SpawnPodSpec};
+use runpod::RunpodError as Error;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tokio::runtime::Runtime;
+use tokio::task::AbortHandle;
+
+const DEFAULT_API_URL: &str = "https://api.runpod.io/graphql";
+
+create_exception!(
+    runpod_rs,
+    RunpodError,
+    PyException,
+    "A Runpod API call failed."
+);
+create_exception!(runpod_rs, AuthenticationError, RunpodError);
+create_exception!(runpod_rs, NotFoundError, RunpodError);
+create_exception!(runpod_rs, RateLimitedError, RunpodError);
+create_exception!(runpod_rs, InsufficientCapacityError, RunpodError);
+create_exception!(runpod_rs, InsufficientBalanceError, RunpodError);
+
+fn to_py_err(err: Error) -> PyErr {
+    let message = err.to_string();
+    match err {
+        Error::AuthenticationFailed(_) => AuthenticationError::new_err(message),
+        Error::NotFound(_) | Error::PodNotFound(_) => NotFoundError::new_err(message),
+        Error::RateLimited => RateLimitedError::new_err(message),
+        Error::InsufficientCapacity(_) => InsufficientCapacityError::new_err(message),
+        Error::InsufficientBalance(_) => InsufficientBalanceError::new_err(message),
+        Error::InvalidInput(_) => PyValueError::new_err(message),
+        _ => RunpodError::new_err(message),
+    }
+}
+
+fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| Runtime::new().expect("Failed to start tokio runtime"))
+}
+
+/// Calls that have not handed their result to the event loop yet.
+static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+
+struct InFlight;
+
+impl InFlight {
+    fn start() -> Self {
+        IN_FLIGHT.fetch_add(1, Ordering::SeqCst);
+        InFlight
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        IN_FLIGHT.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Registered with `atexit`. A runtime thread that is running Python code when the
+/// interpreter starts finalizing is killed by CPython, which crashes the process, so
+/// let outstanding calls finish first.
+#[pyfunction]
+fn _wait_for_calls(py: Python<'_>) {
+    py.allow_threads(|| {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while IN_FLIGHT.load(Ordering::SeqCst) > 0 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    })
+}
+
+/// Resolves an asyncio future on its event loop's thread, unless it was cancelled.
+#[pyfunction]
+fn _resolve(future: &Bound<'_, PyAny>, value: PyObject, error: Option<PyObject>) -> PyResult<()> {
+    if future.call_method0("done")?.is_truthy()? {
+        return Ok(());
+    }
+    match error {
+        Some(error) => future.call_method1("set_exception", (error,))?,
+        None => future.call_method1("set_result", (value,))?,
+    };
+    Ok(())
+}
+
+/// Done callback that aborts the Rust task when its asyncio future is cancelled.
+#[pyclass]
+struct AbortOnCancel(AbortHandle);
+
+#[pymethods]
+impl AbortOnCancel {
+    fn __call__(&self, future: &Bound<'_, PyAny>) -> PyResult<()> {
+        if future.call_method0("cancelled")?.is_truthy()? {
+            self.0.abort();
+        }
+        Ok(())
+    }
+}
+
+/// Run `fut` on the module's runtime and return an asyncio future for its result, bound
+/// to the running event loop.
+fn future_into_py<F, T>(py: Python<'_>, fut: F) -> PyResult<Bound<'_, PyAny>>
+where
+    F: Future<Output = PyResult<T>> + Send + 'static,
+    T: for<'py> IntoPyObject<'py> + Send + 'static,
+{
+    let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
+    let future = event_loop.call_method0("create_future")?;
+    let (event_loop_ref, future_ref) = (event_loop.unbind(), future.clone().unbind());
+    let in_flight = InFlight::start();
+    let task = runtime().spawn(async move {
+        let result = fut.await;
+        Python::with_gil(|py| {
+            let (value, error) = match result.and_then(|value| value.into_py_any(py)) {
+                Ok(value) => (value, None),
+                Err(err) => (py.None(), Some(err.into_value(py).into_any())),
+            };
+            let event_loop = event_loop_ref.bind(py);
+            // Nobody is left to await a call whose event loop has been closed.
+            if matches!(
+                event_loop
+                    .call_method0("is_closed")
+                    .and_then(|c| c.is_truthy()),
+                Ok(true)
+            ) {
+                return;
+            }
+            let resolved = wrap_pyfunction!(_resolve, py).and_then(|resolve| {
+                event_loop.call_method1("call_soon_threadsafe", (resolve, future_ref, value, error))
+            });
+            if let Err(err) = resolved {
+                err.write_unraisable(py, None);
+            }
+        });
+        drop(in_flight);
+    });
+    future.call_method1("add_done_callback", (AbortOnCancel(task.abort_handle()),))?;
+    Ok(future)
+}
+
+/// A frozen pyclass with read-only attributes, a dataclass-style `__repr__` and `to_dict`.
+macro_rules! record {
+    ($(#[$meta:meta])* $name:ident { $($field:ident: $ty:ty,)* }) => {
+        $(#[$meta])*
+        #[pyclass(module = "runpod_rs", get_all, frozen)]
+        #[derive(Debug, Clone)]
+        pub struct $name {
+            $(pub $field: $ty,)*
+        }
+
+        #[pymethods]
+        impl $name {
+            fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+                let fields = [$(
+                    format!(
+                        "{}={}",
+                        stringify!($field),
+                        self.$field.clone().into_pyobject(py)?.repr()?
+                    ),
+                )*];
+                Ok(format!("{}({})", stringify!($name), fields.join(", ")))
+            }
+
+            /// The attributes as a dict, like `dataclasses.asdict`.
+            fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+                let dict = PyDict::new(py);
+                $(dict.set_item(stringify!($field), self.$field.clone())?;)*
+                Ok(dict)
+            }
+        }
+    };
+}
+
+record!(Pod {
+    id: String,
+    name: String,
+    pod_type: Option<String>,
+    desired_status: String,
+    image_name: String,
+    gpu_type_id: Option<String>,
+    gpu_count: i64,
+    vcpu_count: f64,
+    memory_in_gb: f64,
+    volume_in_gb: Option<f64>,
+    container_disk_in_gb: i64,
+    adjusted_cost_per_hr: f64,
+    lowest_bid_price_to_resume: Option<f64>,
+    created_at: String,
+    last_started_at: String,
+    uptime_in_seconds: Option<i64>,
+    gpu_utilization: Option<f64>,
+    location: String,
+    env: Vec<String>,
+});
+
+impl From<runpod::types::Pod> for Pod {
+    fn from(pod: runpod::types::Pod) -> Self {
+        Self {
+            uptime_in_seconds: pod.uptime_in_seconds(),
+            gpu_utilization: pod.gpu_utilization(),
+            id: pod.id,
+            name: pod.name,
+            pod_type: pod.pod_type.map(|t| t.to_string()),
+            desired_status: pod.desired_status.to_string(),
+            image_name: pod.image_name,
+            gpu_type_id: pod.machine.gpu_type.map(|t| t.id),
+            gpu_count: pod.gpu_count,
+            vcpu_count: pod.vcpu_count,
+            memory_in_gb: pod.memory_in_gb,
+            volume_in_gb: pod.volume_in_gb,
+            container_disk_in_gb: pod.container_disk_in_gb,
+            adjusted_cost_per_hr: pod.adjusted_cost_per_hr,
+            lowest_bid_price_to_resume: pod.lowest_bid_price_to_resume,
+            created_at: pod.created_at.to_string(),
+            last_started_at: pod.last_started_at.to_string(),
+            location: pod.machine.location,
+            env: pod.env,
+        }
+    }
+}
+
+record!(
+    /// A GPU type with its secure and community cloud prices. Offers from
+    /// `list_gpus()` without `secure_cloud` combine both clouds.
+    GpuOffer {
+        id: String,
+        display_name: String,
+        manufacturer: Option<String>,
+        memory_in_gb: Option<i64>,
+        secure_cloud: Option<bool>,
+        community_cloud: Option<bool>,
+        secure_price: Option<f64>,
+        community_price: Option<f64>,
+        secure_spot_price: Option<f64>,
+        community_spot_price: Option<f64>,
+        one_week_price: Option<f64>,
+        one_month_price: Option<f64>,
+        three_month_price: Option<f64>,
+        six_month_price: Option<f64>,
+        max_gpu_count: Option<i64>,
+        max_gpu_count_secure_cloud: Option<i64>,
+        max_gpu_count_community_cloud: Option<i64>,
+        stock_status: Option<String>,
+    }
+);
+
+impl From<runpod::types::GpuOffer> for GpuOffer {
+    fn from(gpu: runpod::types::GpuOffer) -> Self {
+        Self {
+            id: gpu.id,
+            display_name: gpu.display_name,
+            manufacturer: gpu.manufacturer,
+            memory_in_gb: gpu.memory_in_gb,
+            secure_cloud: gpu.secure_cloud,
+            community_cloud: gpu.community_cloud,
+            secure_price: gpu.secure_price,
+            community_price: gpu.community_price,
+            secure_spot_price: gpu.secure_spot_price,
+            community_spot_price: gpu.community_spot_price,
+            one_week_price: gpu.one_week_price,
+            one_month_price: gpu.one_month_price,
+            three_month_price: gpu.three_month_price,
+            six_month_price: gpu.six_month_price,
+            max_gpu_count: gpu.max_gpu_count,
+            max_gpu_count_secure_cloud: gpu.max_gpu_count_secure_cloud,
+            max_gpu_count_community_cloud: gpu.max_gpu_count_community_cloud,
+            stock_status: gpu.lowest_price.and_then(|p| p.stock_status),
+        }
+    }
+}
+
+record!(Template {
+    id: Option<String>,
+    name: Option<String>,
+    image_name: Option<String>,
+    docker_args: Option<String>,
+    container_registry_auth_id: Option<String>,
+    env: BTreeMap<String, String>,
+    start_ssh: Option<bool>,
+});
+
+impl From<runpod::types::Template> for Template {
+    fn from(template: runpod::types::Template) -> Self {
+        Self {
+            id: template.id,
+            name: template.name,
+            image_name: template.image_name,
+            docker_args: template.docker_args,
+            container_registry_auth_id: template.container_registry_auth_id,
+            env: template
+                .env
+                .into_iter()
+                .flatten()
+                .map(|var| (var.key, var.value))
+                .collect(),
+            start_ssh: template.start_ssh,
+        }
+    }
+}
+
+/// Async Runpod API client. Without `api_key`, the key and URL are read from
+/// `~/.runpod/config.toml`, like the CLI does, and `team` picks one of the team keys
+/// listed there.
+#[pyclass(module = "runpod_rs", frozen)]
+pub struct RunpodClient {
+    client: runpod::RunpodClient,
+}
+
+#[pymethods]
+impl RunpodClient {
+    #[new]
+    #[pyo3(signature = (api_key=None, api_url=None, team=None))]
+    fn new(
+        api_key: Option<String>,
+        api_url: Option<String>,
+        team: Option<String>,
+    ) -> PyResult<Self> {
+        let mut config =
+            match (api_key, team) {
+                (Some(_), Some(_)) => return Err(PyValueError::new_err(
+                    "pass either api_key or team, not both; a team's api_key already selects it",
+                )),
+                (Some(apikey), None) => Config::new(apikey, DEFAULT_API_URL),
+                (None, team) => {
+                    let config = Config::try_from_env().map_err(to_py_err)?;
+                    match team {
+                        Some(team) => config.for_team(&team).map_err(to_py_err)?,
+                        None => config,
+                    }
+                }
+            };
+        if let Some(api_url) = api_url {
+            config.apiurl = api_url;
+        }
+        // runpod::RunpodClient::new panics on a bad URL.
+        url::Url::parse(&config.apiurl).map_err(|e| {
+            PyValueError::new_err(format!("invalid api_url {:?}: {e}", config.apiurl))
+        })?;
+        Ok(Self {
+            client: runpod::RunpodClient::new(config),
+        })
+    }
+
+    fn list_pods<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let pods = client.list_pods().await.map_err(to_py_err)?;
+            Ok(pods.into_iter().map(Pod::from).collect::<Vec<_>>())
+        })
+    }
+
+    /// The pod, or `None` if there is no pod with this id.
+    fn get_pod<'py>(&self, py: Python<'py>, pod_id: String) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let pod = client.get_pod(&pod_id).await.map_err(to_py_err)?;
+            Ok(pod.map(Pod::from))
+        })
+    }
+
+    /// Spawn a pod and return its id.
+    #[pyo3(signature = (
+        name,
+        gpu_type_id,
+        template_id,
+        gpu_count=1,
+        spot=false,
+        bid_per_gpu=None,
+        container_disk_in_gb=None,
+        env=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn spawn_pod<'py>(
+        &self,
+        py: Python<'py>,
+        name: String,
+        gpu_type_id: String,
+        template_id: String,
+        gpu_count: i64,
+        spot: bool,
+        bid_per_gpu: Option<f64>,
+        container_disk_in_gb: Option<i64>,
+        env: Option<BTreeMap<String, String>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut spec = SpawnPodSpec::builder()
+            .name(name)
+            .gpu_type_id(gpu_type_id)
+            .template_id(template_id)
+            .gpu_count(gpu_count)
+            .spot(spot)
+            .env(
+                env.into_iter()
+                    .flatten()
+                    .map(|(key, value)| EnvironmentVariable::new(key, value))
+                    .collect(),
+            )
+            .build();
+        spec.bid_per_gpu = bid_per_gpu;
+        spec.container_disk_in_gb = container_disk_in_gb;
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            client.spawn_pod(spec).await.map_err(to_py_err)
+        })
+    }
+
+    /// Stop a pod and return its new status.
+    fn stop_pod<'py>(&self, py: Python<'py>, pod_id: String) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let status = client.stop_pod(&pod_id).await.map_err(to_py_err)?;
+            Ok(status.to_string())
+        })
+    }
+
+    fn terminate_pod<'py>(&self, py: Python<'py>, pod_id: String) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            client.terminate_pod(&pod_id).await.map_err(to_py_err)
+        })
+    }
+
+    /// GPU types with prices. `secure_cloud=None` queries both clouds and merges the
+    /// offers, keeping each cloud's price and availability.
+    #[pyo3(signature = (secure_cloud=None))]
+    fn list_gpus<'py>(
+        &self,
+        py: Python<'py>,
+        secure_cloud: Option<bool>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let gpus = client.list_gpus(secure_cloud).await.map_err(to_py_err)?;
+            Ok(gpus.into_iter().map(GpuOffer::from).collect::<Vec<_>>())
+        })
+    }
+
+    fn get_templates<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let templates = client.get_templates().await.map_err(to_py_err)?;
+            Ok(templates
+                .into_iter()
+                .map(Template::from)
+                .collect::<Vec<_>>())
+        })
+    }
+}
+
+#[pymodule]
+fn runpod_rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    py.import("atexit")?
+        .call_method1("register", (wrap_pyfunction!(_wait_for_calls, m)?,))?;
+    m.add_class::<RunpodClient>()?;
+    m.add_class::<Pod>()?;
+    m.add_class::<GpuOffer>()?;
+    m.add_class::<Template>()?;
+    m.add("RunpodError", py.get_type::<RunpodError>())?;
+    m.add("AuthenticationError", py.get_type::<AuthenticationError>())?;
+    m.add("NotFoundError", py.get_type::<NotFoundError>())?;
+    m.add("RateLimitedError", py.get_type::<RateLimitedError>())?;
+    m.add(
+        "InsufficientCapacityError",
+        py.get_type::<InsufficientCapacityError>(),
+    )?;
+    m.add(
+        "InsufficientBalanceError",
+        py.get_type::<InsufficientBalanceError>(),
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::ffi::c_str;
+    use runpod::testing::{FakeGpu, FakeRunpod};
+    use std::sync::Mutex;
+    use tokio::net::TcpListener;
+
+    /// `IN_FLIGHT` is global, so tests that count calls must not overlap.
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    const SCRIPT: &std::ffi::CStr = c_str!(
+        r#"
+import asyncio
+
+async def spawn_and_list(client):
+    pod_id = await client.spawn_pod("exp", "RTX 4090", "tpl", env={"FOO": "1"})
+    pods = await client.list_pods()
+    return pod_id, [pod.to_dict() for pod in pods]
+
+async def stop(client, pod_id):
+    try:
+        await client.stop_pod(pod_id)
+    except Exception as e:
+        return e
+
+async def cancel_list(client):
+    future = client.list_pods()
+    await asyncio.sleep(0.05)
+    future.cancel()
+    return future.cancelled()
+
+def leave_pending(client):
+    loop = asyncio.new_event_loop()
+    async def start():
+        return client.list_pods()
+    loop.run_until_complete(start())
+    loop.close()
+"#
+    );
+
+    fn with_script<R>(f: impl FnOnce(Python<'_>, &Bound<'_, PyModule>) -> R) -> R {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let script =
+                PyModule::from_code(py, SCRIPT, c_str!("tests.py"), c_str!("tests")).unwrap();
+            f(py, &script)
+        })
+    }
+
+    /// `asyncio.run` the script's coroutine function `name`.
+    fn run<'py>(
+        script: &Bound<'py, PyModule>,
+        name: &str,
+        args: impl pyo3::call::PyCallArgs<'py>,
+    ) -> Bound<'py, PyAny> {
+        let coroutine = script.getattr(name).unwrap().call1(args).unwrap();
+        script
+            .py()
+            .import("asyncio")
+            .unwrap()
+            .call_method1("run", (coroutine,))
+            .unwrap()
+    }
+
+    fn client(apiurl: String) -> RunpodClient {
+        RunpodClient {
+            client: runpod::RunpodClient::new(Config::new(runpod::testing::FAKE_API_KEY, apiurl)),
+        }
+    }
+
+    fn wait_for_calls() -> bool {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while IN_FLIGHT.load(Ordering::SeqCst) > 0 {
+            if Instant::now() > deadline {
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        true
+    }
+
+    #[test]
+    fn awaits_calls_against_fake_api() {
+        let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let fake = runtime().block_on(FakeRunpod::start());
+        fake.add_gpu(FakeGpu::new("RTX 4090", 0.69, 1));
+
+        with_script(|py, script| {
+            let client = Py::new(py, client(fake.url())).unwrap();
+            let result = run(script, "spawn_and_list", (client.clone_ref(py),));
+            let (pod_id, pods): (String, Vec<Bound<'_, PyDict>>) = result.extract().unwrap();
+            assert_eq!(pods.len(), 1);
+            let id: String = pods[0].get_item("id").unwrap().unwrap().extract().unwrap();
+            assert_eq!(id, pod_id);
+
+            let error = run(script, "stop", (client, "missing"));
+            assert!(error.is_instance_of::<NotFoundError>());
+        });
+    }
+
+    #[test]
+    fn cancelling_aborts_the_request() {
+        let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        // Accepts connections and never answers, so only an abort ends the call.
+        let listener = runtime()
+            .block_on(TcpListener::bind("127.0.0.1:0"))
+            .unwrap();
+        let url = format!("http://{}/graphql", listener.local_addr().unwrap());
+        let server = runtime().spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                connections.push(stream);
+            }
+        });
+
+        with_script(|py, script| {
+            let cancelled = run(script, "cancel_list", (client(url),));
+            assert!(cancelled.extract::<bool>().unwrap());
+            py.allow_threads(|| assert!(wait_for_calls(), "cancelled call still running"));
+        });
+        server.abort();
+    }
+
+    #[test]
+    fn exit_waits_for_calls_of_closed_loops() {
+        let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let fake = runtime().block_on(FakeRunpod::start());
+
+        with_script(|py, script| {
+            script
+                .getattr("leave_pending")
+                .unwrap()
+                .call1((client(fake.url()),))
+                .unwrap();
+            _wait_for_calls(py);
+            assert_eq!(IN_FLIGHT.load(Ordering::SeqCst), 0);
+        });
+    }
+
+    #[test]
+    fn rejects_invalid_api_url() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let err = RunpodClient::new(Some("key".into()), Some("not a url".into()), None)
+                .err()
+                .unwrap();
+            assert!(err.is_instance_of::<PyValueError>(py));
+        });
+    }
+}
@@ -140,6 +140,7 @@
         packages = with pkgs; [
           cargo-audit
           cargo-watch
+          maturin
         ];
 
         checks = self.checks.${system};